@@ -12,7 +12,7 @@ fn basic() {
     let material_left = Lambertian::new(&Colour::new(0.0, 0.0, 1.0));
     let material_right = Lambertian::new(&Colour::new(1.0, 0.0, 0.0));
 
-    let mut world = hittable_list![
+    let world = hittable_list![
         Rc::new(Sphere::new(
             Point3::new(-r, 0.0, -1.0),
             r,
@@ -38,11 +38,11 @@ fn basic() {
         .focus_dist(3.4)
         .build();
 
-    cam.render(stdout, &mut world).unwrap();
+    cam.render(stdout, &world).unwrap();
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
-    c.bench_function("basic", |b| b.iter(|| basic()));
+    c.bench_function("basic", |b| b.iter(basic));
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -11,7 +11,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let material_left = Lambertian::new(&Colour::new(0.0, 0.0, 1.0));
     let material_right = Lambertian::new(&Colour::new(1.0, 0.0, 0.0));
 
-    let mut world = hittable_list![
+    let world = hittable_list![
         Rc::new(Sphere::new(
             Point3::new(-r, 0.0, -1.0),
             r,
@@ -37,6 +37,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .focus_dist(3.4)
         .build();
 
-    cam.render(stdout, &mut world)?;
+    cam.render(stdout, &world)?;
     Ok(())
 }
@@ -15,7 +15,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let material_bubble = Rc::new(Dielectric::new(1.00 / 1.50));
     let material_right = Rc::new(Metal::new(&Colour::new(0.8, 0.6, 0.2), 1.0));
 
-    let mut world = hittable_list![
+    let world = hittable_list![
         Rc::new(Sphere::new(
             Point3::new(0.0, -100.5, -1.0),
             100.0,
@@ -56,6 +56,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .focus_dist(3.4)
         .build();
 
-    cam.render(stdout, &mut world)?;
+    cam.render(stdout, &world)?;
     Ok(())
 }
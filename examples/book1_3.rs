@@ -84,7 +84,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .focus_dist(10.0)
         .build();
 
-    cam.render(stdout, &mut world)?;
+    cam.render(stdout, &world)?;
 
     Ok(())
 }
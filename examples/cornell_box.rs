@@ -0,0 +1,92 @@
+use ray_tracing::{
+    aov::Aov,
+    camera::{CameraBuilder, CancellationToken, StderrObserver},
+    hittable::HittableList,
+    material::{DiffuseLight, Lambertian},
+    Colour, Point3, Quad, Sphere, Vec3,
+};
+use std::{fs::File, io::BufWriter, path::PathBuf, rc::Rc};
+
+/// Renders the Cornell box to stdout. If a directory is given, the auxiliary outputs are also
+/// written to it as PFM images, one per output.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let stdout = std::io::stdout();
+    let aov_dir = std::env::args().nth(1).map(PathBuf::from);
+
+    let mut world = HittableList::default();
+
+    let red = Rc::new(Lambertian::new(&Colour::new(0.65, 0.05, 0.05)));
+    let white = Rc::new(Lambertian::new(&Colour::new(0.73, 0.73, 0.73)));
+    let green = Rc::new(Lambertian::new(&Colour::new(0.12, 0.45, 0.15)));
+    let light = Rc::new(DiffuseLight::new(&Colour::new(15.0, 15.0, 15.0)));
+
+    world.add(Rc::new(Quad::new(
+        Point3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        green,
+    )));
+    world.add(Rc::new(Quad::new(
+        Point3::new(0.0, 0.0, 555.0),
+        Vec3::new(0.0, 0.0, -555.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        red,
+    )));
+    world.add(Rc::new(Quad::new(
+        Point3::new(0.0, 555.0, 0.0),
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        white.clone(),
+    )));
+    world.add(Rc::new(Quad::new(
+        Point3::new(0.0, 0.0, 555.0),
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -555.0),
+        white.clone(),
+    )));
+    world.add(Rc::new(Quad::new(
+        Point3::new(555.0, 0.0, 555.0),
+        Vec3::new(-555.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        white.clone(),
+    )));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(190.0, 90.0, 190.0),
+        90.0,
+        white,
+    )));
+
+    // The light faces down into the box, and is sampled directly at every diffuse bounce.
+    let lights = Rc::new(Quad::new(
+        Point3::new(213.0, 554.0, 227.0),
+        Vec3::new(130.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 105.0),
+        light,
+    ));
+    world.add(lights.clone());
+
+    let cam = CameraBuilder::default()
+        .aspect_ratio(1.0)
+        .image_width(600)
+        .samples_per_pixel(64)
+        .max_depth(50)
+        .vfov(40.0)
+        .lookfrom(Point3::new(278.0, 278.0, -800.0))
+        .lookat(Point3::new(278.0, 278.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .background(Colour::new(0.0, 0.0, 0.0))
+        .lights(lights)
+        .aovs(aov_dir.is_some())
+        .build();
+
+    let output = cam.render_with(&world, &mut StderrObserver, &CancellationToken::new());
+    output.image.write_ppm(stdout)?;
+    if let (Some(dir), Some(aovs)) = (aov_dir, output.aovs) {
+        for aov in Aov::ALL {
+            let file = File::create(dir.join(format!("{}.pfm", aov.name())))?;
+            aovs.image(aov).write_pfm(BufWriter::new(file))?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use ray_tracing::{
+    camera::CameraBuilder,
+    hittable::HittableList,
+    integrator::PathIntegrator,
+    material::{Dielectric, DiffuseLight, Lambertian, Material},
+    Colour, Point3, Quad, Sphere, Vec3,
+};
+use std::rc::Rc;
+
+/// Renders spheres of dispersive glass and diamond under a small bright light, which split its
+/// white light into coloured caustics on the floor.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let stdout = std::io::stdout();
+
+    let mut world = HittableList::default();
+    world.add(Rc::new(Quad::new(
+        Point3::new(-20.0, 0.0, -20.0),
+        Vec3::new(40.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 40.0),
+        Rc::new(Lambertian::new(&Colour::new(0.8, 0.8, 0.8))),
+    )));
+
+    let materials: [Rc<dyn Material>; 3] = [
+        Rc::new(Dielectric::fused_silica()),
+        Rc::new(Dielectric::bk7()),
+        Rc::new(Dielectric::diamond()),
+    ];
+    for (k, material) in materials.into_iter().enumerate() {
+        world.add(Rc::new(Sphere::new(
+            Point3::new(2.5 * k as f32 - 2.5, 1.0, 0.0),
+            1.0,
+            material,
+        )));
+    }
+
+    let light = Rc::new(Quad::new(
+        Point3::new(-4.0, 6.0, -2.5),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+        Rc::new(DiffuseLight::new(&Colour::new(100.0, 100.0, 100.0))),
+    ));
+    world.add(light.clone());
+
+    let mut cam = CameraBuilder::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(600)
+        .samples_per_pixel(500)
+        .vfov(30.0)
+        .lookfrom(Point3::new(0.0, 8.0, 12.0))
+        .lookat(Point3::new(0.0, 0.5, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .background(Colour::new(0.0, 0.0, 0.0))
+        .lights(light)
+        .integrator(PathIntegrator::new(20).spectral(true))
+        .build();
+
+    cam.render(stdout, &world)?;
+
+    Ok(())
+}
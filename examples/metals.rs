@@ -0,0 +1,52 @@
+use ray_tracing::{
+    camera::CameraBuilder,
+    hittable::HittableList,
+    material::{Conductor, Lambertian, Material},
+    Colour, Point3, Sphere, Vec3,
+};
+use std::rc::Rc;
+
+/// Renders rows of measured metals, each getting rougher from left to right.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let stdout = std::io::stdout();
+
+    let mut world = HittableList::default();
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(&Colour::new(0.5, 0.5, 0.5))),
+    )));
+
+    let metals: [fn(f32) -> Conductor; 4] = [
+        Conductor::gold,
+        Conductor::copper,
+        Conductor::aluminium,
+        Conductor::silver,
+    ];
+    let roughnesses = [0.0, 0.2, 0.4, 0.7];
+    for (row, metal) in metals.iter().enumerate() {
+        for (column, &roughness) in roughnesses.iter().enumerate() {
+            let material: Rc<dyn Material> = Rc::new(metal(roughness));
+            world.add(Rc::new(Sphere::new(
+                Point3::new(2.2 * column as f32 - 3.3, 1.0, -2.2 * row as f32),
+                1.0,
+                material,
+            )));
+        }
+    }
+
+    let mut cam = CameraBuilder::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(800)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(30.0)
+        .lookfrom(Point3::new(0.0, 6.0, 12.0))
+        .lookat(Point3::new(0.0, 0.5, -3.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .build();
+
+    cam.render(stdout, &world)?;
+
+    Ok(())
+}
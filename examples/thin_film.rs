@@ -0,0 +1,51 @@
+use ray_tracing::{
+    camera::CameraBuilder,
+    hittable::HittableList,
+    material::{Conductor, Dielectric, Lambertian, Material, Metal, ThinFilm},
+    Colour, Point3, Quad, Sphere, Vec3,
+};
+use std::rc::Rc;
+
+/// Renders a soap bubble, a coated glass ball, oily steel and tarnished copper, whose thin film
+/// coatings reflect colours that shift from the centre of each sphere to its edges.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let stdout = std::io::stdout();
+
+    let mut world = HittableList::default();
+    world.add(Rc::new(Quad::new(
+        Point3::new(-20.0, 0.0, -20.0),
+        Vec3::new(40.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 40.0),
+        Rc::new(Lambertian::new(&Colour::new(0.5, 0.5, 0.5))),
+    )));
+
+    let dark_steel = Metal::new(&Colour::new(0.3, 0.3, 0.3), 0.0);
+    let materials: [Rc<dyn Material>; 4] = [
+        Rc::new(Dielectric::new(1.0).thin_film(ThinFilm::new(500.0, 1.33))),
+        Rc::new(Dielectric::bk7().thin_film(ThinFilm::new(100.0, 1.38))),
+        Rc::new(dark_steel.thin_film(ThinFilm::new(350.0, 1.45))),
+        Rc::new(Conductor::copper(0.0).thin_film(ThinFilm::new(150.0, 2.6))),
+    ];
+    for (k, material) in materials.into_iter().enumerate() {
+        world.add(Rc::new(Sphere::new(
+            Point3::new(2.5 * k as f32 - 3.75, 1.0, 0.0),
+            1.0,
+            material,
+        )));
+    }
+
+    let mut cam = CameraBuilder::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(600)
+        .samples_per_pixel(100)
+        .vfov(30.0)
+        .lookfrom(Point3::new(0.0, 3.0, 12.0))
+        .lookat(Point3::new(0.0, 1.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .background(Colour::new(0.7, 0.8, 1.0))
+        .build();
+
+    cam.render(stdout, &world)?;
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//! The multiple importance sampling test scene from Eric Veach's thesis: four glossy plates of
+//! increasing roughness reflecting four spherical lights of increasing size but equal power.
+//!
+//! Pass `bsdf` to only sample the materials, or `balance` to use the balance heuristic instead
+//! of the default power heuristic. Material sampling alone is noisy where smooth plates reflect
+//! small lights, and light sampling alone where rough plates reflect large lights; the combined
+//! render is clean everywhere.
+use ray_tracing::{
+    camera::CameraBuilder,
+    hittable::HittableList,
+    integrator::{MisHeuristic, PathIntegrator},
+    material::{DiffuseLight, Lambertian, Metal},
+    Colour, Point3, Quad, Sphere, Vec3,
+};
+use std::rc::Rc;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let stdout = std::io::stdout();
+    let mode = std::env::args().nth(1).unwrap_or_default();
+
+    let lookfrom = Point3::new(0.0, 2.0, 15.0);
+    let light_centre = Point3::new(0.0, 4.0, -3.0);
+
+    let mut world = HittableList::default();
+    let mut lights = HittableList::default();
+
+    // Lights of increasing radius, with radiance scaled so each emits the same power.
+    for (k, radius) in [0.03_f32, 0.1, 0.3, 0.9].into_iter().enumerate() {
+        let radiance = 0.8 / (radius * radius);
+        let light = Rc::new(Sphere::new(
+            light_centre + Vec3::new(-3.75 + 2.5 * k as f32, 0.0, 0.0),
+            radius,
+            Rc::new(DiffuseLight::new(&Colour::new(
+                radiance, radiance, radiance,
+            ))),
+        ));
+        world.add(light.clone());
+        lights.add(light);
+    }
+
+    // Plates of decreasing roughness, each angled to reflect the lights towards the camera.
+    for (k, fuzz) in [0.3_f32, 0.1, 0.03, 0.01].into_iter().enumerate() {
+        let centre = Point3::new(0.0, -1.5 + 0.3 * k as f32, 4.0 - 1.6 * k as f32);
+        let normal =
+            ((lookfrom - centre).normalize() + (light_centre - centre).normalize()).normalize();
+        let u = Vec3::new(10.0, 0.0, 0.0);
+        let v = normal.cross(u).normalize() * 1.4;
+        world.add(Rc::new(Quad::new(
+            centre - 0.5 * u - 0.5 * v,
+            u,
+            v,
+            Rc::new(Metal::new(&Colour::new(0.7, 0.7, 0.7), fuzz)),
+        )));
+    }
+
+    // A dark backdrop behind the plates and lights.
+    world.add(Rc::new(Quad::new(
+        Point3::new(-20.0, -4.0, -6.0),
+        Vec3::new(40.0, 0.0, 0.0),
+        Vec3::new(0.0, 20.0, 0.0),
+        Rc::new(Lambertian::new(&Colour::new(0.2, 0.2, 0.2))),
+    )));
+
+    let mut builder = CameraBuilder::default()
+        .aspect_ratio(4.0 / 3.0)
+        .image_width(800)
+        .samples_per_pixel(16)
+        .max_depth(8)
+        .vfov(28.0)
+        .lookfrom(lookfrom)
+        .lookat(Point3::new(0.0, 1.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .background(Colour::new(0.0, 0.0, 0.0));
+    match mode.as_str() {
+        "bsdf" => {}
+        "balance" => {
+            builder = builder
+                .lights(Rc::new(lights))
+                .integrator(PathIntegrator::new(8).mis_heuristic(MisHeuristic::Balance))
+        }
+        _ => builder = builder.lights(Rc::new(lights)),
+    }
+    let mut cam = builder.build();
+
+    cam.render(stdout, &world)?;
+
+    Ok(())
+}
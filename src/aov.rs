@@ -0,0 +1,349 @@
+use crate::{hittable::HitRecord, image::Image, integrator::Scene, Colour, Point3, Ray, Vec3};
+use std::collections::HashMap;
+
+/// An auxiliary output variable: a per-pixel buffer describing what the camera sees, recorded
+/// alongside the rendered image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Aov {
+    /// Distance from the camera to the first hit.
+    Depth,
+    /// World-space surface normal at the first hit, facing the camera.
+    Normal,
+    /// Albedo of the material at the first hit, see [`Material::albedo`].
+    ///
+    /// [`Material::albedo`]: crate::material::Material::albedo
+    Albedo,
+    /// Index of the first object hit in the world list.
+    ObjectId,
+    /// Number of the material at the first hit, in the order materials were first seen.
+    MaterialId,
+    /// World-space position of the first hit.
+    Position,
+    /// Number of samples taken in the pixel.
+    SampleCount,
+}
+
+impl Aov {
+    /// Every auxiliary output, in the order of the variants.
+    pub const ALL: [Aov; 7] = [
+        Aov::Depth,
+        Aov::Normal,
+        Aov::Albedo,
+        Aov::ObjectId,
+        Aov::MaterialId,
+        Aov::Position,
+        Aov::SampleCount,
+    ];
+
+    /// A short lower case name for the output, suitable for a file name or image layer.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::aov::Aov;
+    /// assert_eq!(Aov::ObjectId.name(), "object_id");
+    /// ```
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aov::Depth => "depth",
+            Aov::Normal => "normal",
+            Aov::Albedo => "albedo",
+            Aov::ObjectId => "object_id",
+            Aov::MaterialId => "material_id",
+            Aov::Position => "position",
+            Aov::SampleCount => "sample_count",
+        }
+    }
+}
+
+/// Per-pixel buffers of the auxiliary outputs, accumulated from the first hit of each camera
+/// sample.
+///
+/// Normals and albedo are averaged over every sample taken in a pixel, with samples that miss
+/// the scene counting as zero, so they are anti-aliased like the image itself. Depth and position
+/// are averaged over the samples that hit something. Object and material IDs cannot be averaged,
+/// so they are taken from the first sample to hit something in the pixel.
+#[derive(Debug, Clone)]
+pub struct AovBuffers {
+    /// Buffer width in pixels.
+    width: u32,
+    /// Buffer height in pixels.
+    height: u32,
+    /// Count of samples taken in each pixel.
+    samples: Vec<u32>,
+    /// Count of samples that hit something in each pixel.
+    hits: Vec<u32>,
+    /// Sum of the first hit distances of each pixel.
+    depth: Vec<f32>,
+    /// Sum of the first hit normals of each pixel.
+    normal: Vec<Vec3>,
+    /// Sum of the first hit albedos of each pixel.
+    albedo: Vec<Colour>,
+    /// Sum of the first hit positions of each pixel.
+    position: Vec<Point3>,
+    /// Object ID of the first sample to hit something in each pixel.
+    object_id: Vec<Option<usize>>,
+    /// Material ID of the first sample to hit something with a material in each pixel.
+    material_id: Vec<Option<usize>>,
+    /// IDs assigned to the materials seen so far, keyed by their address.
+    materials: HashMap<*const (), usize>,
+}
+
+impl AovBuffers {
+    /// Creates new empty buffers with the given dimensions.
+    ///
+    /// # Parameters
+    /// - `width`: The buffer width in pixels.
+    /// - `height`: The buffer height in pixels.
+    ///
+    /// # Returns
+    /// New `AovBuffers` with no samples.
+    pub fn new(width: u32, height: u32) -> Self {
+        let len = width as usize * height as usize;
+        Self {
+            width,
+            height,
+            samples: vec![0; len],
+            hits: vec![0; len],
+            depth: vec![0.0; len],
+            normal: vec![Vec3::ZERO; len],
+            albedo: vec![Colour::default(); len],
+            position: vec![Point3::ZERO; len],
+            object_id: vec![None; len],
+            material_id: vec![None; len],
+            materials: HashMap::new(),
+        }
+    }
+
+    /// Buffer width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Buffer height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Traces the camera ray `r` of a sample taken in the pixel at column `i` and row `j` to its
+    /// first hit and records what it hit.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use ray_tracing::{
+    ///     aov::AovBuffers, hittable::HittableList, integrator::Scene, material::Lambertian,
+    ///     Colour, Point3, Ray, Sphere, Vec3,
+    /// };
+    ///
+    /// let mut world = HittableList::default();
+    /// let red = Rc::new(Lambertian::new(&Colour::new(1.0, 0.0, 0.0)));
+    /// world.add(Rc::new(Sphere::new(Point3::new(0.0, 0.0, -3.0), 1.0, red)));
+    /// let scene = Scene { world: &world, lights: None, background: None };
+    ///
+    /// let mut aovs = AovBuffers::new(1, 1);
+    /// aovs.add_sample(0, 0, &Ray::new(Point3::ZERO, Vec3::new(0.0, 0.0, -0.5)), &scene);
+    /// assert!((aovs.depth(0, 0) - 2.0).abs() < 1e-5);
+    /// assert_eq!(aovs.albedo(0, 0), Colour::new(1.0, 0.0, 0.0));
+    /// assert_eq!(aovs.object_id(0, 0), Some(0));
+    /// ```
+    pub fn add_sample(&mut self, i: u32, j: u32, r: &Ray, scene: &Scene) {
+        let index = self.index(i, j);
+        self.samples[index] += 1;
+
+        // Trace a unit length ray so that the hit's `t` is its distance from the camera.
+        let r = Ray::new(*r.origin(), r.direction().normalize());
+        let mut rec = HitRecord::default();
+        if !scene.hit(&r, &mut rec) {
+            return;
+        }
+        self.hits[index] += 1;
+        self.depth[index] += rec.t;
+        self.normal[index] += rec.normal;
+        self.position[index] += rec.p;
+        if self.object_id[index].is_none() {
+            self.object_id[index] = Some(rec.object_id);
+        }
+        if let Some(mat) = &rec.mat {
+            self.albedo[index] += mat.albedo(&rec);
+            if self.material_id[index].is_none() {
+                let next_id = self.materials.len();
+                let id = *self
+                    .materials
+                    .entry(std::rc::Rc::as_ptr(mat) as *const ())
+                    .or_insert(next_id);
+                self.material_id[index] = Some(id);
+            }
+        }
+    }
+
+    /// Number of samples taken in the pixel at column `i` and row `j`.
+    pub fn sample_count(&self, i: u32, j: u32) -> u32 {
+        self.samples[self.index(i, j)]
+    }
+
+    /// Mean distance from the camera to the first hit of the pixel at column `i` and row `j`, or
+    /// infinity if no sample hit anything.
+    pub fn depth(&self, i: u32, j: u32) -> f32 {
+        let index = self.index(i, j);
+        match self.hits[index] {
+            0 => f32::INFINITY,
+            n => self.depth[index] / n as f32,
+        }
+    }
+
+    /// Mean world-space normal at the first hit of the pixel at column `i` and row `j`, facing the
+    /// camera. It is shorter than unit length where the pixel covers an edge.
+    pub fn normal(&self, i: u32, j: u32) -> Vec3 {
+        let index = self.index(i, j);
+        self.normal[index] / self.samples[index].max(1) as f32
+    }
+
+    /// Mean albedo at the first hit of the pixel at column `i` and row `j`.
+    pub fn albedo(&self, i: u32, j: u32) -> Colour {
+        let index = self.index(i, j);
+        self.albedo[index] * (1.0 / self.samples[index].max(1) as f32)
+    }
+
+    /// Mean world-space position of the first hit of the pixel at column `i` and row `j`, or the
+    /// origin if no sample hit anything.
+    pub fn position(&self, i: u32, j: u32) -> Point3 {
+        let index = self.index(i, j);
+        self.position[index] / self.hits[index].max(1) as f32
+    }
+
+    /// Index in the world list of the object seen in the pixel at column `i` and row `j`, or
+    /// `None` if no sample hit anything.
+    pub fn object_id(&self, i: u32, j: u32) -> Option<usize> {
+        self.object_id[self.index(i, j)]
+    }
+
+    /// ID of the material seen in the pixel at column `i` and row `j`, or `None` if no sample hit
+    /// anything with a material. Materials are numbered in the order they are first seen, so IDs
+    /// are consistent within a render but may change between renders.
+    pub fn material_id(&self, i: u32, j: u32) -> Option<usize> {
+        self.material_id[self.index(i, j)]
+    }
+
+    /// Converts one of the outputs into an image.
+    ///
+    /// Depth, normal, position and sample count values are stored unchanged in every channel
+    /// they need, so the image should be saved in a floating point format such as
+    /// [`Image::write_pfm`] to keep them. IDs are shown as a distinct colour for each ID, with
+    /// black where nothing was hit.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{aov::{Aov, AovBuffers}, Colour};
+    /// let aovs = AovBuffers::new(2, 2);
+    /// let image = aovs.image(Aov::SampleCount);
+    /// assert_eq!(image.get(1, 1), Colour::new(0.0, 0.0, 0.0));
+    /// ```
+    pub fn image(&self, aov: Aov) -> Image {
+        let mut image = Image::new(self.width, self.height);
+        for j in 0..self.height {
+            for i in 0..self.width {
+                let colour = match aov {
+                    Aov::Depth => {
+                        let depth = self.depth(i, j);
+                        Colour::new(depth, depth, depth)
+                    }
+                    Aov::Normal => {
+                        let n = self.normal(i, j);
+                        Colour::new(n.x, n.y, n.z)
+                    }
+                    Aov::Albedo => self.albedo(i, j),
+                    Aov::ObjectId => id_colour(self.object_id(i, j)),
+                    Aov::MaterialId => id_colour(self.material_id(i, j)),
+                    Aov::Position => {
+                        let p = self.position(i, j);
+                        Colour::new(p.x, p.y, p.z)
+                    }
+                    Aov::SampleCount => {
+                        let count = self.sample_count(i, j) as f32;
+                        Colour::new(count, count, count)
+                    }
+                };
+                image.set(i, j, colour);
+            }
+        }
+        image
+    }
+
+    fn index(&self, i: u32, j: u32) -> usize {
+        assert!(i < self.width && j < self.height);
+        j as usize * self.width as usize + i as usize
+    }
+}
+
+/// A colour that is easy to tell apart from the colours of nearby IDs, or black for no ID.
+fn id_colour(id: Option<usize>) -> Colour {
+    let Some(id) = id else {
+        return Colour::default();
+    };
+    // Scatter consecutive IDs around the colour cube with a multiplicative hash.
+    let hash = (id as u32).wrapping_add(1).wrapping_mul(0x9e37_79b9);
+    let channel = |shift: u32| 0.2 + 0.8 * ((hash >> shift) & 0xff) as f32 / 255.0;
+    Colour::new(channel(24), channel(16), channel(8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::Lambertian, Sphere};
+    use std::rc::Rc;
+
+    #[test]
+    fn ids_distinguish_objects_and_materials() {
+        let red = Rc::new(Lambertian::new(&Colour::new(1.0, 0.0, 0.0)));
+        let blue = Rc::new(Lambertian::new(&Colour::new(0.0, 0.0, 1.0)));
+        let world = crate::hittable_list![
+            Rc::new(Sphere::new(Point3::new(-2.0, 0.0, -5.0), 1.0, blue.clone())),
+            Rc::new(Sphere::new(Point3::new(0.0, 0.0, -5.0), 1.0, red)),
+            Rc::new(Sphere::new(Point3::new(2.0, 0.0, -5.0), 1.0, blue)),
+        ];
+        let scene = Scene {
+            world: &world,
+            lights: None,
+            background: None,
+        };
+
+        let mut aovs = AovBuffers::new(4, 1);
+        for (i, x) in [0.0, -2.0, 2.0, 10.0].into_iter().enumerate() {
+            let r = Ray::new(Point3::ZERO, Vec3::new(x, 0.0, -5.0));
+            aovs.add_sample(i as u32, 0, &r, &scene);
+        }
+
+        let objects: Vec<_> = (0..4).map(|i| aovs.object_id(i, 0)).collect();
+        assert_eq!(objects, [Some(1), Some(0), Some(2), None]);
+        // Materials are numbered as they are first seen, and shared materials share an ID.
+        let materials: Vec<_> = (0..4).map(|i| aovs.material_id(i, 0)).collect();
+        assert_eq!(materials, [Some(0), Some(1), Some(1), None]);
+        assert_eq!(aovs.depth(3, 0), f32::INFINITY);
+    }
+
+    #[test]
+    fn misses_darken_averaged_normals_and_albedo() {
+        let white = Rc::new(Lambertian::new(&Colour::new(1.0, 1.0, 1.0)));
+        let world = crate::hittable_list![Rc::new(Sphere::new(
+            Point3::new(0.0, 0.0, -5.0),
+            1.0,
+            white
+        ))];
+        let scene = Scene {
+            world: &world,
+            lights: None,
+            background: None,
+        };
+
+        let mut aovs = AovBuffers::new(1, 1);
+        aovs.add_sample(0, 0, &Ray::new(Point3::ZERO, -Vec3::Z), &scene);
+        aovs.add_sample(0, 0, &Ray::new(Point3::ZERO, Vec3::Z), &scene);
+
+        assert_eq!(aovs.sample_count(0, 0), 2);
+        assert_eq!(aovs.albedo(0, 0), Colour::new(0.5, 0.5, 0.5));
+        assert!((aovs.normal(0, 0) - Vec3::new(0.0, 0.0, 0.5)).length() < 1e-5);
+        // Depth and position only average the samples that hit.
+        assert!((aovs.depth(0, 0) - 4.0).abs() < 1e-5);
+        assert!((aovs.position(0, 0) - Point3::new(0.0, 0.0, -4.0)).length() < 1e-5);
+    }
+}
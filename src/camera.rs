@@ -1,13 +1,86 @@
 use std::io::Write;
+use std::ops::Range;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use crate::{
-    hittable::{HitRecord, Hittable},
-    interval::Interval,
-    random::{random_f32, random_in_unit_disk},
+    aov::AovBuffers,
+    denoise::Denoiser,
+    film::Film,
+    filter::Filter,
+    hittable::Hittable,
+    image::{Image, Region},
+    integrator::{Integrator, PathIntegrator, Scene},
+    sampler::{sample_unit_disk, IndependentSampler, Sampler},
     util::degrees_to_radians,
-    Colour, Point3, Ray, Vec3,
+    Colour, Point3, Ray, Vec2, Vec3,
 };
 
+mod adaptive;
+mod progress;
+mod progressive;
+
+pub use adaptive::AdaptiveSampling;
+pub use progress::{CancellationToken, Progress, RenderObserver, SilentObserver, StderrObserver};
+pub use progressive::ProgressiveRender;
+
+/// The result of rendering a scene with a [`Camera`].
+#[derive(Debug, Clone)]
+pub struct RenderOutput {
+    /// The rendered image, cropped to the rendered region and denoised if the camera has a
+    /// denoiser. Pixels that were not reached before a cancellation are black.
+    pub image: Image,
+    /// The accumulated samples the image was resolved from, including the per-pixel sample
+    /// counts.
+    pub film: Film,
+    /// The part of the camera frame that was rendered. Pixel `(i, j)` of the image is pixel
+    /// `(region.x + i, region.y + j)` of the full frame.
+    pub region: Region,
+    /// The auxiliary outputs of the rendered region, if the camera records them or has a
+    /// denoiser.
+    pub aovs: Option<AovBuffers>,
+    /// Whether the render was cancelled before every pixel was rendered.
+    pub cancelled: bool,
+}
+
+impl RenderOutput {
+    /// The number of samples every pixel of the image received.
+    ///
+    /// Some pixels may have more samples, when adaptive sampling is enabled or a time-budgeted
+    /// render stopped part way through a pass.
+    pub fn samples_per_pixel(&self) -> u32 {
+        self.film.min_sample_count()
+    }
+
+    /// Composites the rendered region into a full-size frame at its position in the camera frame.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{
+    ///     camera::{CameraBuilder, CancellationToken, SilentObserver},
+    ///     hittable::HittableList,
+    ///     image::{Image, Region},
+    ///     Colour,
+    /// };
+    ///
+    /// let world = HittableList::default();
+    /// let cam = CameraBuilder::default()
+    ///     .image_width(8)
+    ///     .region(Region::new(2, 2, 4, 4))
+    ///     .build();
+    /// let output = cam.render_with(&world, &mut SilentObserver, &CancellationToken::new());
+    /// assert_eq!(output.image.width(), 4);
+    ///
+    /// let mut frame = Image::new(cam.image_width(), cam.image_height());
+    /// output.composite(&mut frame);
+    /// assert_eq!(frame.get(0, 0), Colour::default());
+    /// assert_eq!(frame.get(2, 2), output.image.get(0, 0));
+    /// ```
+    pub fn composite(&self, frame: &mut Image) {
+        frame.paste(&self.image, self.region.x, self.region.y);
+    }
+}
+
 pub struct Camera {
     /// Ratio of image width over height.
     #[allow(unused)]
@@ -17,6 +90,7 @@ pub struct Camera {
     /// Count of random samples for each pixel.
     samples_per_pixel: u32,
     /// Maximum number of ray bounces into scene.
+    #[allow(unused)]
     max_depth: u32,
     /// Vertical view angle (field of view)
     #[allow(unused)]
@@ -45,8 +119,6 @@ pub struct Camera {
     pixel_delta_u: Vec3,
     /// Offset to pixel below
     pixel_delta_v: Vec3,
-    /// Color scale factor for a sum of pixel samples
-    pixel_sample_scale: f32,
     /// Camera frame basis vector
     #[allow(unused)]
     u: Vec3,
@@ -60,56 +132,419 @@ pub struct Camera {
     defocus_disk_u: Vec3,
     /// Defocus disk vertical radius
     defocus_disk_v: Vec3,
+    /// Adaptive sampling settings, if enabled
+    adaptive_sampling: Option<AdaptiveSampling>,
+    /// Part of the frame to render
+    region: Region,
+    /// Generator of pixel, lens and bounce samples
+    sampler: Box<dyn Sampler>,
+    /// Pixel reconstruction filter
+    filter: Filter,
+    /// Emissive objects for the integrator to sample directly, if any
+    lights: Option<Rc<dyn Hittable>>,
+    /// Colour of rays that escape the scene, or a sky gradient if not set
+    background: Option<Colour>,
+    /// Light transport algorithm
+    integrator: Box<dyn Integrator>,
+    /// Whether to record auxiliary outputs alongside the image
+    aovs: bool,
+    /// Denoising pass applied to the rendered image, if any
+    denoiser: Option<Denoiser>,
 }
 
 impl Camera {
+    /// Renders the world and writes it to `stdout` as a PPM image, reporting progress on stderr.
     pub fn render(
         &mut self,
-        mut stdout: impl Write,
+        stdout: impl Write,
         world: &impl Hittable,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        println!("P3\n{} {}\n255", self.image_width, self.image_height);
-
-        for j in 0..self.image_height {
-            eprintln!("Scanlines remaining: {}", self.image_height - j);
-            for i in 0..self.image_width {
-                let mut pixel_colour = Colour::new(0.0, 0.0, 0.0);
-                for _ in 0..self.samples_per_pixel {
-                    let r = self.get_ray(i, j);
-                    pixel_colour += self.ray_colour(&r, self.max_depth, world);
+        let output = self.render_with(world, &mut StderrObserver, &CancellationToken::new());
+        output.image.write_ppm(stdout)?;
+        Ok(())
+    }
+
+    /// Renders the world into an in-memory image.
+    ///
+    /// If adaptive sampling is enabled the render is progressive, see
+    /// [`Camera::render_progressive`].
+    ///
+    /// # Parameters
+    /// - `world`: The scene to render.
+    /// - `observer`: Receives an event after each completed row and when the render stops.
+    /// - `cancel`: Checked before each pixel; once cancelled the render stops and returns the
+    ///   partial image, leaving the pixels that were not rendered black.
+    ///
+    /// # Returns
+    /// The rendered image along with whether the render was cancelled.
+    pub fn render_with(
+        &self,
+        world: &impl Hittable,
+        observer: &mut impl RenderObserver,
+        cancel: &CancellationToken,
+    ) -> RenderOutput {
+        if self.adaptive_sampling.is_some() {
+            return self.render_progressive(world, observer, cancel);
+        }
+
+        let start = Instant::now();
+        let region = self.region;
+        let mut film = Film::with_filter(region.width, region.height, self.filter);
+        let mut aovs = self.new_aovs();
+        let mut sampler = self.sampler.clone();
+        let scene = self.scene(world);
+        let mut progress = Progress {
+            rows_completed: 0,
+            total_rows: region.height,
+            passes_completed: 0,
+            total_passes: 1,
+            samples_completed: 0,
+            total_samples: self.sample_budget(),
+            elapsed: Duration::ZERO,
+        };
+        let mut cancelled = false;
+
+        'rows: for j in 0..region.height {
+            for i in 0..region.width {
+                if cancel.is_cancelled() {
+                    cancelled = true;
+                    break 'rows;
+                }
+                for s in 0..self.samples_per_pixel {
+                    let (r, offset) = self.get_ray(region.x + i, region.y + j, s, sampler.as_mut());
+                    if let Some(aovs) = &mut aovs {
+                        aovs.add_sample(i, j, &r, &scene);
+                    }
+                    let colour = self.integrator.radiance(&r, &scene, sampler.as_mut());
+                    film.add_sample_at(i, j, offset, colour);
                 }
-                writeln!(&mut stdout, "{}", &(self.pixel_sample_scale * pixel_colour))?;
+                progress.samples_completed += self.samples_per_pixel as u64;
             }
+            progress.rows_completed += 1;
+            progress.elapsed = start.elapsed();
+            observer.on_progress(&progress);
+        }
+        if !cancelled {
+            let samples = 0..self.samples_per_pixel;
+            let stop = || cancel.is_cancelled();
+            cancelled = !self.trace_margin(&scene, &mut film, sampler.as_mut(), samples, stop);
         }
 
-        eprintln!("Done.");
-        Ok(())
+        if !cancelled {
+            progress.passes_completed = 1;
+        }
+        progress.elapsed = start.elapsed();
+        observer.on_finish(&progress, cancelled);
+        RenderOutput {
+            image: self.resolve(&film, aovs.as_ref()),
+            film,
+            region,
+            aovs,
+            cancelled,
+        }
     }
 
-    fn ray_colour(&self, r: &Ray, depth: u32, world: &impl Hittable) -> Colour {
-        if depth <= 0 {
-            return Colour::new(0.0, 0.0, 0.0);
+    /// Starts a progressive render of the world.
+    ///
+    /// Nothing is traced until [`ProgressiveRender::render_pass`] is called, so the caller decides
+    /// how many passes to run and when to look at the intermediate results.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{camera::CameraBuilder, hittable::HittableList};
+    ///
+    /// let world = HittableList::default();
+    /// let cam = CameraBuilder::default().image_width(4).build();
+    /// let mut render = cam.progressive(&world);
+    /// render.render_pass();
+    /// render.render_pass();
+    /// assert_eq!(render.passes(), 2);
+    /// let preview = render.snapshot();
+    /// assert_eq!(preview.width(), 4);
+    /// ```
+    pub fn progressive<'a, H: Hittable>(&'a self, world: &'a H) -> ProgressiveRender<'a, H> {
+        ProgressiveRender::new(self, world)
+    }
+
+    /// Renders the world progressively, running one sample pass per sample per pixel.
+    ///
+    /// Unlike [`Camera::render_with`], every pixel receives a sample before any pixel receives its
+    /// second, and the observer is handed the accumulated film after each pass so it can display
+    /// a preview. Cancelling stops the render and returns the image resolved from the samples
+    /// traced so far.
+    ///
+    /// With adaptive sampling enabled, passes after the first `min_samples` only sample the
+    /// pixels whose relative error is still above the threshold, and the render stops once every
+    /// pixel has converged or the sample budget of `samples_per_pixel` per pixel is spent.
+    pub fn render_progressive(
+        &self,
+        world: &impl Hittable,
+        observer: &mut impl RenderObserver,
+        cancel: &CancellationToken,
+    ) -> RenderOutput {
+        self.render_passes(world, None, observer, cancel)
+    }
+
+    /// Renders the world progressively until the time budget runs out.
+    ///
+    /// Rather than stopping after `samples_per_pixel` passes, passes keep being added until
+    /// `budget` has elapsed, so the image gets as many samples as the time allows. The pass
+    /// running when time is up is stopped part way, so its samples only reach some of the pixels;
+    /// [`RenderOutput::samples_per_pixel`] reports the sample count every pixel reached. With
+    /// adaptive sampling enabled, the render can also finish early once every pixel has
+    /// converged or reached its maximum samples.
+    ///
+    /// Progress events estimate the total samples from the rate achieved so far, so the
+    /// estimated time remaining counts down to the end of the budget.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use ray_tracing::{
+    ///     camera::{CameraBuilder, CancellationToken, SilentObserver},
+    ///     hittable::HittableList,
+    /// };
+    ///
+    /// let world = HittableList::default();
+    /// let cam = CameraBuilder::default().image_width(4).build();
+    /// let output = cam.render_for(
+    ///     &world,
+    ///     Duration::from_millis(10),
+    ///     &mut SilentObserver,
+    ///     &CancellationToken::new(),
+    /// );
+    /// assert!(!output.cancelled);
+    /// println!("Reached {} samples per pixel", output.samples_per_pixel());
+    /// ```
+    pub fn render_for(
+        &self,
+        world: &impl Hittable,
+        budget: Duration,
+        observer: &mut impl RenderObserver,
+        cancel: &CancellationToken,
+    ) -> RenderOutput {
+        self.render_passes(world, Some(budget), observer, cancel)
+    }
+
+    /// Runs progressive passes until the sample budget, or the time budget if one is given, is
+    /// spent.
+    fn render_passes(
+        &self,
+        world: &impl Hittable,
+        time_budget: Option<Duration>,
+        observer: &mut impl RenderObserver,
+        cancel: &CancellationToken,
+    ) -> RenderOutput {
+        let start = Instant::now();
+        let deadline = time_budget.map(|budget| start + budget);
+        let mut render = self.progressive(world);
+        // Adaptive sampling caps the samples of each pixel, even within a time budget.
+        let max_passes = match self.adaptive_sampling {
+            Some(adaptive) => adaptive
+                .max_samples
+                .unwrap_or(8 * self.samples_per_pixel)
+                .max(1),
+            None if time_budget.is_some() => u32::MAX,
+            None => self.samples_per_pixel,
+        };
+        let sample_cap = self.region.width as u64 * self.region.height as u64 * max_passes as u64;
+        let budget = match time_budget {
+            Some(_) => u64::MAX,
+            None => self.sample_budget(),
+        };
+        let mut progress = Progress {
+            rows_completed: 0,
+            total_rows: self.region.height,
+            passes_completed: 0,
+            total_passes: if time_budget.is_some() { 0 } else { max_passes },
+            samples_completed: 0,
+            total_samples: if time_budget.is_some() { 0 } else { budget },
+            elapsed: Duration::ZERO,
+        };
+        let update_progress = |progress: &mut Progress| {
+            progress.elapsed = start.elapsed();
+            let elapsed = progress.elapsed.as_secs_f64();
+            // Extrapolate the samples traced so far to the end of the time budget, once there
+            // is a rate to extrapolate, but no further than the sample cap.
+            if let Some(time_budget) = time_budget {
+                if elapsed > 0.0 && progress.samples_completed > 0 {
+                    let rate = progress.samples_completed as f64 / elapsed;
+                    let extrapolated = (rate * time_budget.as_secs_f64()) as u64;
+                    progress.total_samples =
+                        extrapolated.min(sample_cap).max(progress.samples_completed);
+                }
+            }
+        };
+        let mut cancelled = false;
+
+        for pass in 0..max_passes {
+            if progress.samples_completed >= budget {
+                break;
+            }
+            // Adaptive sampling only skips converged pixels once every pixel has had enough
+            // samples for its error estimate to be trusted.
+            let adaptive = self
+                .adaptive_sampling
+                .filter(|adaptive| pass >= adaptive.min_samples);
+            let samples_before = progress.samples_completed;
+            let traced = render.render_pass_with(
+                || cancel.is_cancelled() || deadline.is_some_and(|d| Instant::now() >= d),
+                budget - samples_before,
+                |film, i, j| match adaptive {
+                    Some(adaptive) => film.relative_error(i, j) > adaptive.threshold,
+                    None => true,
+                },
+                |rows, traced| {
+                    progress.rows_completed = rows;
+                    progress.samples_completed = samples_before + traced;
+                    update_progress(&mut progress);
+                    observer.on_progress(&progress);
+                },
+            );
+            let Some(traced) = traced else {
+                cancelled = cancel.is_cancelled();
+                break;
+            };
+            if traced == 0 {
+                break;
+            }
+            progress.samples_completed = samples_before + traced;
+            progress.passes_completed = render.passes();
+            update_progress(&mut progress);
+            observer.on_pass(&progress, render.film());
         }
-        let mut rec = HitRecord::default();
-        if world.hit(r, Interval::new(0.001, f32::INFINITY), &mut rec) {
-            let mut scattered = Ray::default();
-            let mut attenuation = Colour::default();
-            if let Some(mat) = &rec.mat {
-                if mat.scatter(r, &rec, &mut attenuation, &mut scattered) {
-                    return attenuation * self.ray_colour(&mut scattered, depth - 1, world);
+
+        update_progress(&mut progress);
+        observer.on_finish(&progress, cancelled);
+        let (film, aovs) = render.into_buffers();
+        RenderOutput {
+            image: self.resolve(&film, aovs.as_ref()),
+            film,
+            region: self.region,
+            aovs,
+            cancelled,
+        }
+    }
+
+    /// Rendered image width in pixels, for the full frame.
+    pub fn image_width(&self) -> u32 {
+        self.image_width
+    }
+
+    /// Rendered image height in pixels, for the full frame.
+    pub fn image_height(&self) -> u32 {
+        self.image_height
+    }
+
+    /// The part of the frame that is rendered.
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    /// Replaces the light transport algorithm, so the same view can be rendered with a different
+    /// integrator, such as a quick preview before a full render.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{
+    ///     camera::{CameraBuilder, CancellationToken, SilentObserver},
+    ///     hittable::HittableList,
+    ///     integrator::NormalsIntegrator,
+    /// };
+    ///
+    /// let world = HittableList::default();
+    /// let mut cam = CameraBuilder::default().image_width(4).build();
+    /// cam.set_integrator(NormalsIntegrator);
+    /// let output = cam.render_with(&world, &mut SilentObserver, &CancellationToken::new());
+    /// assert_eq!(output.image.width(), 4);
+    /// ```
+    pub fn set_integrator(&mut self, integrator: impl Integrator + 'static) {
+        self.integrator = Box::new(integrator);
+    }
+
+    /// Total number of samples a render of the region traces.
+    fn sample_budget(&self) -> u64 {
+        self.region.width as u64 * self.region.height as u64 * self.samples_per_pixel as u64
+    }
+
+    /// Empty auxiliary output buffers for the region, if the camera records them or needs them
+    /// to guide the denoiser.
+    fn new_aovs(&self) -> Option<AovBuffers> {
+        (self.aovs || self.denoiser.is_some())
+            .then(|| AovBuffers::new(self.region.width, self.region.height))
+    }
+
+    /// Resolves the film into the output image, denoising it if the camera has a denoiser.
+    fn resolve(&self, film: &Film, aovs: Option<&AovBuffers>) -> Image {
+        match (self.denoiser, aovs) {
+            (Some(denoiser), Some(aovs)) => denoiser.denoise(film, aovs),
+            _ => film.snapshot(),
+        }
+    }
+
+    /// The scene seen by the integrator when rendering `world`.
+    fn scene<'a>(&'a self, world: &'a dyn Hittable) -> Scene<'a> {
+        Scene {
+            world,
+            lights: self.lights.as_deref(),
+            background: self.background,
+        }
+    }
+
+    /// Traces the samples `samples` of the pixels around the rendered region whose samples the
+    /// filter spreads into it, and splats them into `film`, so that renders of neighbouring
+    /// regions join up without seams. Stops early when `stop` returns `true`.
+    ///
+    /// # Returns
+    /// Whether every pixel was traced without being stopped.
+    fn trace_margin(
+        &self,
+        scene: &Scene,
+        film: &mut Film,
+        sampler: &mut dyn Sampler,
+        samples: Range<u32>,
+        stop: impl Fn() -> bool,
+    ) -> bool {
+        let region = self.region;
+        let margin = self.filter.radius().ceil() as u32;
+        let x0 = region.x.saturating_sub(margin);
+        let y0 = region.y.saturating_sub(margin);
+        let frame = Region::new(0, 0, self.image_width, self.image_height);
+        let traced = Region::new(
+            x0,
+            y0,
+            region.x + region.width + margin - x0,
+            region.y + region.height + margin - y0,
+        )
+        .intersect(&frame);
+
+        for j in traced.y..traced.y + traced.height {
+            for i in traced.x..traced.x + traced.width {
+                if region.contains(i, j) {
+                    continue;
+                }
+                if stop() {
+                    return false;
+                }
+                for s in samples.clone() {
+                    let (r, offset) = self.get_ray(i, j, s, sampler);
+                    let colour = self.integrator.radiance(&r, scene, sampler);
+                    let (fi, fj) = (i as i32 - region.x as i32, j as i32 - region.y as i32);
+                    film.add_splat(fi, fj, offset, colour);
                 }
             }
-            return Colour::default();
         }
-        let unit_direction = r.direction().normalize();
-        let a = 0.5 * (unit_direction.y + 1.0);
-        (1.0 - a) * Colour::new(1.0, 1.0, 1.0) + a * Colour::new(0.5, 0.7, 1.0)
+        true
     }
 
-    fn get_ray(&self, i: u32, j: u32) -> Ray {
+    fn get_ray(&self, i: u32, j: u32, sample_index: u32, sampler: &mut dyn Sampler) -> (Ray, Vec2) {
         // Construct a camera ray originating from the defocus disk and directed at a randomly
         // sampled point around the pixel location i, j.
-        let offset = self.sample_square();
+        sampler.start_pixel_sample(i, j, sample_index);
+        let offset = self.sample_square(sampler);
+        // The lens sample is always drawn so the following dimensions do not depend on the
+        // defocus angle.
+        let lens_sample = self.defocus_disk_sample(sampler);
         let pixel_sample = self.pixel00_loc
             + ((i as f32 + offset.x) * self.pixel_delta_u)
             + ((j as f32 + offset.y) * self.pixel_delta_v);
@@ -118,26 +553,31 @@ impl Camera {
         let ray_origin = if self.defocus_angle <= 0.0 {
             self.center
         } else {
-            self.defocus_disk_sample()
+            lens_sample
         };
         let ray_direction = pixel_sample - ray_origin;
 
-        return Ray::new(ray_origin, ray_direction);
+        // Also return the sample's offset from the pixel centre so the film can filter it.
+        (
+            Ray::new(ray_origin, ray_direction),
+            Vec2::new(offset.x, offset.y),
+        )
     }
 
-    fn sample_square(&self) -> Vec3 {
+    fn sample_square(&self, sampler: &mut dyn Sampler) -> Vec3 {
         // Returns the vector to a random point in the [-.5,-.5]-[+.5,+.5] unit square.
-        return Vec3::new(random_f32() - 0.5, random_f32() - 0.5, 0.0);
+        let u = sampler.get_2d();
+        Vec3::new(u.x - 0.5, u.y - 0.5, 0.0)
     }
 
-    fn defocus_disk_sample(&self) -> Point3 {
+    fn defocus_disk_sample(&self, sampler: &mut dyn Sampler) -> Point3 {
         // Returns a random point in the camera defocus disk.
-        let p = random_in_unit_disk();
-        return self.center + (p[0] * self.defocus_disk_u) + (p[1] * self.defocus_disk_v);
+        let p = sample_unit_disk(sampler.get_2d());
+        self.center + (p[0] * self.defocus_disk_u) + (p[1] * self.defocus_disk_v)
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct CameraBuilder {
     aspect_ratio: f32,
     image_width: u32,
@@ -149,6 +589,15 @@ pub struct CameraBuilder {
     vup: Vec3,
     defocus_angle: f32,
     focus_dist: f32,
+    adaptive_sampling: Option<AdaptiveSampling>,
+    region: Option<Region>,
+    sampler: Box<dyn Sampler>,
+    filter: Filter,
+    lights: Option<Rc<dyn Hittable>>,
+    background: Option<Colour>,
+    integrator: Option<Box<dyn Integrator>>,
+    aovs: bool,
+    denoiser: Option<Denoiser>,
 }
 
 impl Default for CameraBuilder {
@@ -163,7 +612,16 @@ impl Default for CameraBuilder {
             lookat: Point3::new(0.0, 0.0, -1.0),
             vup: Vec3::new(0.0, 1.0, 0.0),
             defocus_angle: Default::default(),
-            focus_dist: Default::default(),
+            focus_dist: 10.0,
+            adaptive_sampling: None,
+            region: None,
+            sampler: Box::new(IndependentSampler),
+            filter: Filter::default(),
+            lights: None,
+            background: None,
+            integrator: None,
+            aovs: false,
+            denoiser: None,
         }
     }
 }
@@ -193,7 +651,8 @@ impl CameraBuilder {
         }
     }
 
-    /// Maximum number of ray bounces into scene.
+    /// Maximum number of ray bounces into scene, for the default path integrator. Paths are
+    /// normally ended earlier by Russian roulette, so this is only a safety cap.
     pub fn max_depth(self, max_depth: u32) -> Self {
         Self { max_depth, ..self }
     }
@@ -231,10 +690,85 @@ impl CameraBuilder {
         Self { focus_dist, ..self }
     }
 
+    /// Stop sampling pixels once they have converged, spending the saved samples on noisy pixels
+    pub fn adaptive_sampling(self, adaptive_sampling: AdaptiveSampling) -> Self {
+        Self {
+            adaptive_sampling: Some(adaptive_sampling),
+            ..self
+        }
+    }
+
+    /// Part of the frame to render, in pixels of the full image. Defaults to the whole frame.
+    /// Pixels around the region are also traced where the filter spreads their samples into it,
+    /// so that regions rendered separately join up without seams.
+    pub fn region(self, region: Region) -> Self {
+        Self {
+            region: Some(region),
+            ..self
+        }
+    }
+
+    /// Generator of pixel, lens and bounce samples. Defaults to [`IndependentSampler`]
+    pub fn sampler(self, sampler: impl Sampler + 'static) -> Self {
+        Self {
+            sampler: Box::new(sampler),
+            ..self
+        }
+    }
+
+    /// Pixel reconstruction filter. Defaults to a box filter averaging the samples in each pixel
+    pub fn filter(self, filter: Filter) -> Self {
+        Self { filter, ..self }
+    }
+
+    /// Emissive objects for the integrator to sample directly, which greatly reduces the noise
+    /// from small lights. Every object must support [`Hittable::random`]. Defaults to no lights,
+    /// leaving lights to be found by chance
+    pub fn lights(self, lights: Rc<dyn Hittable>) -> Self {
+        Self {
+            lights: Some(lights),
+            ..self
+        }
+    }
+
+    /// Colour of rays that escape the scene. Defaults to a sky gradient
+    pub fn background(self, background: Colour) -> Self {
+        Self {
+            background: Some(background),
+            ..self
+        }
+    }
+
+    /// Light transport algorithm. Defaults to a [`PathIntegrator`] following paths for at most
+    /// `max_depth` bounces
+    pub fn integrator(self, integrator: impl Integrator + 'static) -> Self {
+        Self {
+            integrator: Some(Box::new(integrator)),
+            ..self
+        }
+    }
+
+    /// Record the depth, normal, albedo, object and material IDs, position and sample count of
+    /// each pixel alongside the image, in [`RenderOutput::aovs`]. Tracing the extra first hit of
+    /// each sample costs some render time. Defaults to off
+    pub fn aovs(self, aovs: bool) -> Self {
+        Self { aovs, ..self }
+    }
+
+    /// Denoise the rendered image, which makes renders with few samples per pixel presentable.
+    /// The auxiliary outputs guiding the denoiser are recorded as well. Defaults to no denoising
+    pub fn denoiser(self, denoiser: Denoiser) -> Self {
+        Self {
+            denoiser: Some(denoiser),
+            ..self
+        }
+    }
+
     pub fn build(self) -> Camera {
         let image_height = (self.image_width as f32 / self.aspect_ratio).floor() as u32;
         let image_height = if image_height < 1 { 1 } else { image_height };
-        let pixel_sample_scale = 1.0 / self.samples_per_pixel as f32;
+        let frame = Region::new(0, 0, self.image_width, image_height);
+        let region = self.region.map_or(frame, |region| region.intersect(&frame));
         let center = self.lookfrom;
 
         // Determine the viewport dimensions.
@@ -282,12 +816,725 @@ impl CameraBuilder {
             pixel00_loc,
             pixel_delta_u,
             pixel_delta_v,
-            pixel_sample_scale,
             u,
             v,
             w,
             defocus_disk_u,
             defocus_disk_v,
+            adaptive_sampling: self.adaptive_sampling,
+            region,
+            sampler: self.sampler,
+            filter: self.filter,
+            lights: self.lights,
+            background: self.background,
+            integrator: self
+                .integrator
+                .unwrap_or_else(|| Box::new(PathIntegrator::new(self.max_depth))),
+            aovs: self.aovs,
+            denoiser: self.denoiser,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::film::Film;
+    use crate::{
+        integrator::{
+            AmbientOcclusionIntegrator, DirectLightingIntegrator, MisHeuristic, NormalsIntegrator,
+        },
+        material::{Blend, Lambertian, Material, Metal},
+        Sphere,
+    };
+    use std::rc::Rc;
+
+    fn world() -> crate::hittable::HittableList {
+        crate::hittable_list![Rc::new(Sphere::new(
+            Point3::new(0.0, 0.0, -1.0),
+            0.5,
+            Rc::new(Lambertian::new(&Colour::new(0.5, 0.5, 0.5))),
+        ))]
+    }
+
+    fn camera() -> Camera {
+        CameraBuilder::default()
+            .image_width(8)
+            .samples_per_pixel(2)
+            .max_depth(4)
+            .build()
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<Progress>,
+        passes: Vec<Image>,
+        finished: Option<bool>,
+    }
+
+    impl RenderObserver for Recorder {
+        fn on_progress(&mut self, progress: &Progress) {
+            self.events.push(*progress);
         }
+
+        fn on_pass(&mut self, _progress: &Progress, film: &Film) {
+            self.passes.push(film.snapshot());
+        }
+
+        fn on_finish(&mut self, _progress: &Progress, cancelled: bool) {
+            self.finished = Some(cancelled);
+        }
+    }
+
+    #[test]
+    fn default_focus_distance_frames_the_scene() {
+        // A camera built without a focus distance still spreads its rays over the frame, so the
+        // sphere in the middle is surrounded by sky.
+        let output = camera().render_with(&world(), &mut SilentObserver, &CancellationToken::new());
+        let image = &output.image;
+        let brightness = |c: Colour| c.r() + c.g() + c.b();
+        assert!(image.pixels().iter().all(|&c| brightness(c).is_finite()));
+        let (w, h) = (image.width(), image.height());
+        assert!(brightness(image.get(w / 2, h / 2)) < brightness(image.get(0, 0)));
+    }
+
+    #[test]
+    fn render_reports_progress_per_row() {
+        let mut recorder = Recorder::default();
+        let output = camera().render_with(&world(), &mut recorder, &CancellationToken::new());
+
+        assert!(!output.cancelled);
+        assert_eq!(recorder.events.len(), 8);
+        assert_eq!(recorder.finished, Some(false));
+        let last = recorder.events.last().unwrap();
+        assert_eq!(last.rows_completed, 8);
+        assert_eq!(last.samples_completed, 8 * 8 * 2);
+        assert_eq!(last.fraction(), 1.0);
+        assert_eq!(last.estimated_remaining(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn render_cancelled_returns_partial_image() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let mut recorder = Recorder::default();
+        let output = camera().render_with(&world(), &mut recorder, &cancel);
+
+        assert!(output.cancelled);
+        assert!(recorder.events.is_empty());
+        assert_eq!(recorder.finished, Some(true));
+        assert_eq!(output.image.width(), 8);
+        assert_eq!(output.image.height(), 8);
+        assert!(output
+            .image
+            .pixels()
+            .iter()
+            .all(|p| *p == Colour::default()));
+    }
+
+    #[test]
+    fn render_cancelled_mid_render_keeps_completed_rows() {
+        struct CancelAfterRow(CancellationToken);
+
+        impl RenderObserver for CancelAfterRow {
+            fn on_progress(&mut self, _progress: &Progress) {
+                self.0.cancel();
+            }
+        }
+
+        let cancel = CancellationToken::new();
+        let output = camera().render_with(&world(), &mut CancelAfterRow(cancel.clone()), &cancel);
+
+        assert!(output.cancelled);
+        // The sky gradient is never black, so only the first row has been rendered.
+        assert!((0..8).all(|i| output.image.get(i, 0) != Colour::default()));
+        assert!((0..8).all(|i| output.image.get(i, 1) == Colour::default()));
+    }
+
+    #[test]
+    fn render_progressive_snapshots_each_pass() {
+        let mut recorder = Recorder::default();
+        let output =
+            camera().render_progressive(&world(), &mut recorder, &CancellationToken::new());
+
+        assert!(!output.cancelled);
+        assert_eq!(recorder.passes.len(), 2);
+        assert_eq!(recorder.events.len(), 16);
+        assert_eq!(recorder.finished, Some(false));
+        assert_eq!(recorder.passes.last(), Some(&output.image));
+        let last = recorder.events.last().unwrap();
+        assert_eq!(last.samples_completed, last.total_samples);
+    }
+
+    #[test]
+    fn progressive_accumulates_one_sample_per_pass() {
+        let cam = camera();
+        let world = world();
+        let mut render = cam.progressive(&world);
+        render.render_pass();
+        render.render_pass();
+        render.render_pass();
+
+        assert_eq!(render.passes(), 3);
+        assert_eq!(render.film().sample_count(0, 0), 3);
+        assert_eq!(render.film().sample_count(7, 7), 3);
+    }
+
+    #[test]
+    fn pass_limit_spreads_samples_over_the_image() {
+        let cam = camera();
+        let world = world();
+        let mut render = cam.progressive(&world);
+        let mut rows = Vec::new();
+        let traced = render.render_pass_with(
+            || false,
+            16,
+            |_, _, _| true,
+            |row, traced| rows.push((row, traced)),
+        );
+
+        // The limit runs out a quarter of the way through the pixels, but every row gets its share
+        // rather than the top rows getting all of it.
+        assert_eq!(traced, Some(16));
+        for j in 0..8 {
+            let sampled = (0..8)
+                .filter(|&i| render.film().sample_count(i, j) > 0)
+                .count();
+            assert_eq!(sampled, 2);
+        }
+        assert_eq!(rows.len(), 8);
+        assert_eq!(rows.last(), Some(&(8, 16)));
+    }
+
+    #[test]
+    fn adaptive_sampling_stops_converged_pixels() {
+        let cam = CameraBuilder::default()
+            .image_width(8)
+            .samples_per_pixel(16)
+            .adaptive_sampling(AdaptiveSampling::new(0.05).min_samples(4))
+            .build();
+        let output = cam.render_with(
+            &crate::hittable::HittableList::default(),
+            &mut SilentObserver,
+            &CancellationToken::new(),
+        );
+
+        // The sky barely changes across a pixel so every pixel converges straight away.
+        assert!(!output.cancelled);
+        assert!((0..8).all(|j| (0..8).all(|i| output.film.sample_count(i, j) == 4)));
+    }
+
+    #[test]
+    fn adaptive_sampling_spends_budget_on_noisy_pixels() {
+        let cam = CameraBuilder::default()
+            .image_width(8)
+            .samples_per_pixel(8)
+            .max_depth(4)
+            .adaptive_sampling(AdaptiveSampling::new(0.01).min_samples(4).max_samples(32))
+            .build();
+        let output = cam.render_with(&world(), &mut SilentObserver, &CancellationToken::new());
+
+        let counts: Vec<u32> = (0..8)
+            .flat_map(|j| (0..8).map(move |i| (i, j)))
+            .map(|(i, j)| output.film.sample_count(i, j))
+            .collect();
+        assert!(counts.iter().map(|&n| n as u64).sum::<u64>() <= 8 * 8 * 8);
+        assert!(counts.iter().all(|&n| (4..=32).contains(&n)));
+        assert!(counts.iter().any(|&n| n > 8));
+        assert_eq!(output.film.sample_heatmap().width(), 8);
+    }
+
+    #[test]
+    fn render_for_keeps_adding_passes_until_time_runs_out() {
+        let mut recorder = Recorder::default();
+        let budget = Duration::from_millis(50);
+        let start = Instant::now();
+        let output =
+            camera().render_for(&world(), budget, &mut recorder, &CancellationToken::new());
+
+        assert!(start.elapsed() >= budget);
+        assert!(!output.cancelled);
+        assert_eq!(recorder.finished, Some(false));
+        // Far more passes fit in the budget than the camera's two samples per pixel.
+        assert!(output.samples_per_pixel() > 2);
+        assert_eq!(recorder.passes.len() as u32, output.samples_per_pixel());
+    }
+
+    #[test]
+    fn render_for_respects_adaptive_sample_cap() {
+        // Nothing converges at a zero threshold, so only the cap stops the render early.
+        let cam = CameraBuilder::default()
+            .image_width(8)
+            .samples_per_pixel(2)
+            .adaptive_sampling(AdaptiveSampling::new(0.0).min_samples(1).max_samples(3))
+            .build();
+        let budget = Duration::from_secs(60);
+        let start = Instant::now();
+        let mut recorder = Recorder::default();
+        let output = cam.render_for(&world(), budget, &mut recorder, &CancellationToken::new());
+
+        assert!(start.elapsed() < budget);
+        assert!(!output.cancelled);
+        assert_eq!(output.samples_per_pixel(), 3);
+        // The estimated total never exceeds what the cap allows.
+        assert!(recorder
+            .events
+            .iter()
+            .all(|progress| progress.total_samples <= 8 * 8 * 3));
+    }
+
+    #[test]
+    fn render_for_can_be_cancelled() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let output = camera().render_for(
+            &world(),
+            Duration::from_secs(60),
+            &mut SilentObserver,
+            &cancel,
+        );
+
+        assert!(output.cancelled);
+        assert_eq!(output.samples_per_pixel(), 0);
+    }
+
+    #[test]
+    fn region_is_clamped_to_frame() {
+        let cam = CameraBuilder::default()
+            .image_width(8)
+            .region(Region::new(6, 5, 4, 4))
+            .build();
+        assert_eq!(cam.region(), Region::new(6, 5, 2, 3));
+
+        let mut recorder = Recorder::default();
+        let output = cam.render_progressive(&world(), &mut recorder, &CancellationToken::new());
+        assert_eq!(output.image.width(), 2);
+        assert_eq!(output.image.height(), 3);
+        assert_eq!(recorder.events.last().unwrap().total_rows, 3);
+        assert_eq!(output.samples_per_pixel(), 10);
+    }
+
+    #[test]
+    fn region_composites_into_frame() {
+        let cam = CameraBuilder::default()
+            .image_width(8)
+            .samples_per_pixel(1)
+            .region(Region::new(0, 4, 8, 4))
+            .build();
+        let output = cam.render_with(&world(), &mut SilentObserver, &CancellationToken::new());
+
+        let mut frame = Image::new(8, 8);
+        output.composite(&mut frame);
+        for j in 0..8 {
+            for i in 0..8 {
+                if j < 4 {
+                    assert_eq!(frame.get(i, j), Colour::default());
+                } else {
+                    assert_eq!(frame.get(i, j), output.image.get(i, j - 4));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pasted_regions_match_full_render() {
+        let build = |filter, region: Option<Region>| {
+            let builder = CameraBuilder::default()
+                .image_width(8)
+                .samples_per_pixel(2)
+                .max_depth(4)
+                .sampler(crate::sampler::SobolSampler::new(3))
+                .filter(filter);
+            match region {
+                Some(region) => builder.region(region),
+                None => builder,
+            }
+            .build()
+        };
+        let filters = [
+            Filter::Tent { radius: 1.5 },
+            Filter::gaussian(2.0),
+            Filter::MitchellNetravali {
+                radius: 2.0,
+                b: 1.0 / 3.0,
+                c: 1.0 / 3.0,
+            },
+            Filter::Lanczos {
+                radius: 2.0,
+                tau: 2.0,
+            },
+        ];
+        for filter in filters {
+            for progressive in [false, true] {
+                let render = |cam: Camera| {
+                    let cancel = CancellationToken::new();
+                    if progressive {
+                        cam.render_progressive(&world(), &mut SilentObserver, &cancel)
+                    } else {
+                        cam.render_with(&world(), &mut SilentObserver, &cancel)
+                    }
+                };
+                let full = render(build(filter, None)).image;
+                let mut pasted = Image::new(8, 8);
+                for half in [Region::new(0, 0, 8, 4), Region::new(0, 4, 8, 4)] {
+                    render(build(filter, Some(half))).composite(&mut pasted);
+                }
+                for j in 0..8 {
+                    for i in 0..8 {
+                        let d = full.get(i, j) - pasted.get(i, j);
+                        let error = d.r().abs().max(d.g().abs()).max(d.b().abs());
+                        assert!(error < 1e-4, "{filter:?} at ({i}, {j}): {error}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn wide_filter_blends_neighbouring_pixels() {
+        // Only the sky is visible, so every pixel is a weighted average of sky colours.
+        let world = crate::hittable::HittableList::default();
+        let build = |filter| {
+            CameraBuilder::default()
+                .image_width(8)
+                .samples_per_pixel(4)
+                .filter(filter)
+                .build()
+        };
+        for filter in [Filter::Tent { radius: 1.5 }, Filter::gaussian(2.0)] {
+            let output =
+                build(filter).render_with(&world, &mut SilentObserver, &CancellationToken::new());
+            assert_eq!(output.film.filter(), filter);
+            assert_eq!(output.samples_per_pixel(), 4);
+            let sky = 0.6..=1.0 + 1e-5;
+            assert!(output
+                .image
+                .pixels()
+                .iter()
+                .all(|p| sky.contains(&p.luminance())));
+        }
+    }
+
+    /// A floor lit only by a small light facing down onto it, and the light on its own.
+    fn lit_floor(floor: Rc<dyn Material>) -> (crate::hittable::HittableList, Rc<dyn Hittable>) {
+        let light: Rc<dyn Hittable> = Rc::new(crate::Quad::new(
+            Point3::new(-0.25, 2.0, -0.25),
+            Vec3::new(0.5, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 0.5),
+            Rc::new(crate::material::DiffuseLight::new(&Colour::new(
+                20.0, 20.0, 20.0,
+            ))),
+        ));
+        let floor = Rc::new(crate::Quad::new(
+            Point3::new(-4.0, 0.0, 4.0),
+            Vec3::new(8.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, -8.0),
+            floor,
+        ));
+        (crate::hittable_list![floor, light.clone()], light)
+    }
+
+    fn lit_floor_camera() -> CameraBuilder {
+        CameraBuilder::default()
+            .image_width(8)
+            .samples_per_pixel(64)
+            .max_depth(4)
+            .vfov(40.0)
+            .lookfrom(Point3::new(0.0, 1.0, 3.0))
+            .lookat(Point3::ZERO)
+            .background(Colour::default())
+    }
+
+    /// Mean luminance of the image and mean per-pixel sample variance.
+    fn brightness_and_noise(output: &RenderOutput) -> (f32, f32) {
+        let film = &output.film;
+        let n = (film.width() * film.height()) as f32;
+        let mut brightness = 0.0;
+        let mut noise = 0.0;
+        for j in 0..film.height() {
+            for i in 0..film.width() {
+                brightness += film.pixel(i, j).luminance() / n;
+                noise += film.variance(i, j) / n;
+            }
+        }
+        (brightness, noise)
+    }
+
+    #[test]
+    fn light_sampling_reduces_noise_without_bias() {
+        let (world, light) = lit_floor(Rc::new(Lambertian::new(&Colour::new(0.5, 0.5, 0.5))));
+        let cancel = CancellationToken::new();
+        // Without light sampling the floor is so noisy it needs many more samples to converge.
+        let unsampled = lit_floor_camera()
+            .samples_per_pixel(4096)
+            .build()
+            .render_with(&world, &mut SilentObserver, &cancel);
+        let sampled = lit_floor_camera().lights(light).build().render_with(
+            &world,
+            &mut SilentObserver,
+            &cancel,
+        );
+
+        let (unsampled_brightness, unsampled_noise) = brightness_and_noise(&unsampled);
+        let (sampled_brightness, sampled_noise) = brightness_and_noise(&sampled);
+        assert!((sampled_brightness / unsampled_brightness - 1.0).abs() < 0.1);
+        assert!(sampled_noise < unsampled_noise / 100.0);
+    }
+
+    #[test]
+    fn mis_reduces_noise_on_glossy_surfaces_without_bias() {
+        let (world, light) = lit_floor(Rc::new(Metal::new(&Colour::new(0.8, 0.8, 0.8), 0.2)));
+        let cancel = CancellationToken::new();
+        // Look at the reflection of the light in the floor.
+        let camera = || lit_floor_camera().lookat(Point3::new(0.0, 0.0, 2.0));
+        let reference = camera().samples_per_pixel(4096).build().render_with(
+            &world,
+            &mut SilentObserver,
+            &cancel,
+        );
+        let (reference_brightness, reference_noise) = brightness_and_noise(&reference);
+
+        for heuristic in [MisHeuristic::Balance, MisHeuristic::Power] {
+            let output = camera()
+                .samples_per_pixel(512)
+                .lights(light.clone())
+                .integrator(PathIntegrator::new(4).mis_heuristic(heuristic))
+                .build()
+                .render_with(&world, &mut SilentObserver, &cancel);
+            let (brightness, noise) = brightness_and_noise(&output);
+            assert!((brightness / reference_brightness - 1.0).abs() < 0.1);
+            assert!(noise < reference_noise / 2.0);
+        }
+    }
+
+    #[test]
+    fn blends_with_mirrors_are_lit_like_both_materials() {
+        let white: Rc<dyn Material> = Rc::new(Lambertian::new(&Colour::new(0.5, 0.5, 0.5)));
+        let mirror: Rc<dyn Material> = Rc::new(Metal::new(&Colour::new(0.8, 0.8, 0.8), 0.0));
+        let cancel = CancellationToken::new();
+        // Look at the floor around the reflection of the light.
+        let brightness = |floor| {
+            let (world, light) = lit_floor(floor);
+            let output = lit_floor_camera()
+                .lookat(Point3::new(0.0, 0.0, 2.0))
+                .samples_per_pixel(1024)
+                .lights(light)
+                .sampler(crate::sampler::SobolSampler::new(5))
+                .build()
+                .render_with(&world, &mut SilentObserver, &cancel);
+            brightness_and_noise(&output).0
+        };
+
+        let diffuse = brightness(white.clone());
+        let reflected = brightness(mirror.clone());
+        for weight in [0.25, 0.75] {
+            let expected = (1.0 - weight) * diffuse + weight * reflected;
+            let blend = brightness(Rc::new(Blend::new(white.clone(), mirror.clone(), weight)));
+            assert!(
+                (blend / expected - 1.0).abs() < 0.05,
+                "{weight}: {blend} {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn russian_roulette_keeps_brightness() {
+        // A bright sphere resting on a bright floor, so light bounces between them many times.
+        let white = Rc::new(Lambertian::new(&Colour::new(0.8, 0.8, 0.8)));
+        let world = crate::hittable_list![
+            Rc::new(Sphere::new(Point3::new(0.0, 0.0, -1.0), 0.5, white.clone())),
+            Rc::new(Sphere::new(Point3::new(0.0, -100.5, -1.0), 100.0, white)),
+        ];
+        let render = |russian_roulette_depth| {
+            let output = CameraBuilder::default()
+                .image_width(8)
+                .samples_per_pixel(1024)
+                .integrator(PathIntegrator::new(50).russian_roulette_depth(russian_roulette_depth))
+                .build()
+                .render_with(&world, &mut SilentObserver, &CancellationToken::new());
+            brightness_and_noise(&output).0
+        };
+        let full = render(50);
+        let roulette = render(0);
+        assert!((roulette / full - 1.0).abs() < 0.03);
+    }
+
+    #[test]
+    fn seeded_renders_are_identical() {
+        let render = || {
+            CameraBuilder::default()
+                .image_width(8)
+                .samples_per_pixel(4)
+                .max_depth(50)
+                .sampler(crate::sampler::SobolSampler::new(7))
+                .build()
+                .render_with(&world(), &mut SilentObserver, &CancellationToken::new())
+                .image
+        };
+        let image = render();
+        assert_eq!(image, render());
+
+        // FNV-1a hash of the bits of every pixel, as rendered by the recursive `ray_colour`
+        // that the path state loop replaced.
+        const RECURSIVE_RENDER_HASH: u64 = 0xb153_91f2_e058_c476;
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for j in 0..image.height() {
+            for i in 0..image.width() {
+                let c = image.get(i, j);
+                for byte in [c.r(), c.g(), c.b()]
+                    .iter()
+                    .flat_map(|v| v.to_bits().to_le_bytes())
+                {
+                    hash = (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+                }
+            }
+        }
+        assert_eq!(hash, RECURSIVE_RENDER_HASH);
+    }
+
+    #[test]
+    fn integrators_can_be_switched_per_render() {
+        let world = world();
+        let mut cam = camera();
+        let mut render = |integrator: Box<dyn Integrator>| {
+            cam.integrator = integrator;
+            cam.render_with(&world, &mut SilentObserver, &CancellationToken::new())
+                .image
+        };
+        let normals = render(Box::new(NormalsIntegrator));
+        let occlusion = render(Box::<AmbientOcclusionIntegrator>::default());
+
+        // The centre pixel sees the front of the sphere, facing the camera along +z.
+        let centre = normals.get(4, 4);
+        assert!(centre.b() > 0.9 && (centre.r() - 0.5).abs() < 0.2);
+        // Corners miss the sphere, so show neither normals nor occlusion.
+        assert_eq!(normals.get(0, 0), Colour::default());
+        assert_eq!(occlusion.get(0, 0), Colour::default());
+        // Nothing occludes a single convex sphere.
+        assert_eq!(occlusion.get(4, 4), Colour::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn direct_lighting_matches_path_tracing_without_bounces() {
+        // A floor only lit directly, so a path tracer finds no more light than direct lighting.
+        let (world, light) = lit_floor(Rc::new(Lambertian::new(&Colour::new(0.5, 0.5, 0.5))));
+        let cancel = CancellationToken::new();
+        let path = lit_floor_camera()
+            .lights(light.clone())
+            .build()
+            .render_with(&world, &mut SilentObserver, &cancel);
+        let direct = lit_floor_camera()
+            .lights(light)
+            .integrator(DirectLightingIntegrator::new(4))
+            .build()
+            .render_with(&world, &mut SilentObserver, &cancel);
+        let path_brightness = brightness_and_noise(&path).0;
+        let direct_brightness = brightness_and_noise(&direct).0;
+        assert!((direct_brightness / path_brightness - 1.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn spectral_rendering_matches_rgb_under_white_light() {
+        // Under white light, a surface reflects light of its albedo's colour whether light is
+        // carried as RGB or as a spectrum.
+        let world = crate::hittable_list![Rc::new(Sphere::new(
+            Point3::new(0.0, 0.0, -1.0),
+            0.5,
+            Rc::new(Lambertian::new(&Colour::new(0.8, 0.4, 0.1))),
+        ))];
+        let render = |spectral: bool| {
+            let output = CameraBuilder::default()
+                .image_width(8)
+                .samples_per_pixel(1024)
+                .background(Colour::new(1.0, 1.0, 1.0))
+                .integrator(PathIntegrator::new(4).spectral(spectral))
+                .build()
+                .render_with(&world, &mut SilentObserver, &CancellationToken::new());
+            let pixels = output.film.snapshot();
+            let n = pixels.pixels().len() as f32;
+            pixels
+                .pixels()
+                .iter()
+                .fold(Colour::default(), |sum, &pixel| sum + pixel * (1.0 / n))
+        };
+        let rgb = render(false);
+        let spectral = render(true);
+        for (a, b) in [
+            (rgb.r(), spectral.r()),
+            (rgb.g(), spectral.g()),
+            (rgb.b(), spectral.b()),
+        ] {
+            assert!((a / b - 1.0).abs() < 0.03, "{rgb} {spectral}");
+        }
+    }
+
+    #[test]
+    fn aovs_are_recorded_without_changing_the_image() {
+        let world = world();
+        let cancel = CancellationToken::new();
+        let render = |aovs: bool| {
+            CameraBuilder::default()
+                .image_width(8)
+                .samples_per_pixel(4)
+                .sampler(crate::sampler::SobolSampler::new(3))
+                .aovs(aovs)
+                .build()
+                .render_with(&world, &mut SilentObserver, &cancel)
+        };
+        let plain = render(false);
+        let output = render(true);
+        assert!(plain.aovs.is_none());
+        assert_eq!(plain.image, output.image);
+
+        let aovs = output.aovs.unwrap();
+        assert_eq!(aovs.sample_count(3, 3), 4);
+        // The centre pixel sees the front of the sphere half a unit from the camera.
+        assert!((aovs.depth(4, 4) - 0.5).abs() < 0.05);
+        assert!(aovs.normal(4, 4).z > 0.9);
+        assert_eq!(aovs.object_id(4, 4), Some(0));
+        assert_eq!(aovs.object_id(0, 0), None);
+
+        // Progressive renders record the buffers from the same samples as the film.
+        let progressive = CameraBuilder::default()
+            .image_width(8)
+            .samples_per_pixel(4)
+            .adaptive_sampling(AdaptiveSampling::new(0.05).min_samples(2))
+            .aovs(true)
+            .build()
+            .render_with(&world, &mut SilentObserver, &cancel);
+        let aovs = progressive.aovs.unwrap();
+        for (i, j) in [(0, 0), (4, 4), (7, 2)] {
+            assert_eq!(aovs.sample_count(i, j), progressive.film.sample_count(i, j));
+        }
+    }
+
+    #[test]
+    fn denoising_brings_low_sample_renders_closer_to_reference() {
+        let (world, light) = lit_floor(Rc::new(Lambertian::new(&Colour::new(0.5, 0.5, 0.5))));
+        let cancel = CancellationToken::new();
+        let camera = || {
+            lit_floor_camera()
+                .image_width(32)
+                .sampler(crate::sampler::SobolSampler::new(5))
+        };
+        // Without light sampling, 16 samples leave the light mostly unfound and the floor noisy.
+        let render = |builder: CameraBuilder| {
+            builder
+                .build()
+                .render_with(&world, &mut SilentObserver, &cancel)
+                .image
+        };
+        let reference = render(camera().samples_per_pixel(1024).lights(light));
+        // Compare the gamma corrected brightness, as the images would be displayed.
+        let displayed = |colour: &Colour| colour.luminance().clamp(0.0, 1.0).sqrt();
+        let error = |image: &Image| {
+            let pixels = image.pixels().iter().zip(reference.pixels());
+            pixels
+                .map(|(a, b)| (displayed(a) - displayed(b)).powi(2))
+                .sum::<f32>()
+        };
+        let noisy = render(camera().samples_per_pixel(16));
+        let denoised = render(camera().samples_per_pixel(16).denoiser(Denoiser::default()));
+        assert!(error(&denoised) < error(&noisy) / 4.0);
     }
 }
@@ -0,0 +1,54 @@
+/// Settings for adaptive sampling, where each pixel stops receiving samples once its estimated
+/// error is low enough.
+///
+/// The total sample budget of an adaptive render is still `samples_per_pixel` times the number
+/// of pixels, but samples saved on pixels that converge quickly, such as flat sky, are spent on
+/// the noisy pixels instead, up to `max_samples` each.
+///
+/// # Example
+/// ```
+/// use ray_tracing::camera::{AdaptiveSampling, CameraBuilder};
+///
+/// let cam = CameraBuilder::default()
+///     .samples_per_pixel(100)
+///     .adaptive_sampling(AdaptiveSampling::new(0.02).min_samples(8).max_samples(400))
+///     .build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveSampling {
+    /// Relative error below which a pixel is considered converged.
+    pub(super) threshold: f32,
+    /// Samples every pixel receives before its error is checked.
+    pub(super) min_samples: u32,
+    /// Most samples a single pixel can receive, if set.
+    pub(super) max_samples: Option<u32>,
+}
+
+impl AdaptiveSampling {
+    /// Creates adaptive sampling settings that stop sampling a pixel once its relative error,
+    /// see [`Film::relative_error`](crate::film::Film::relative_error), drops below `threshold`.
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            threshold,
+            min_samples: 16,
+            max_samples: None,
+        }
+    }
+
+    /// Samples every pixel receives before its error is checked. Defaults to 16.
+    pub fn min_samples(self, min_samples: u32) -> Self {
+        Self {
+            min_samples,
+            ..self
+        }
+    }
+
+    /// Most samples a single pixel can receive. Defaults to eight times the camera's samples per
+    /// pixel.
+    pub fn max_samples(self, max_samples: u32) -> Self {
+        Self {
+            max_samples: Some(max_samples),
+            ..self
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::Duration;
+
+use crate::film::Film;
+
+/// A snapshot of how far a render has progressed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    /// Number of image rows that have been fully rendered.
+    pub rows_completed: u32,
+    /// Total number of image rows in the render.
+    pub total_rows: u32,
+    /// Number of sample passes over the whole image that have been completed.
+    pub passes_completed: u32,
+    /// Total number of sample passes over the whole image, or zero if it is not known in advance
+    /// as for a time-budgeted render.
+    pub total_passes: u32,
+    /// Number of camera ray samples traced so far.
+    pub samples_completed: u64,
+    /// Total number of camera ray samples the render will trace. Time-budgeted renders estimate
+    /// this from the sampling rate so far.
+    pub total_samples: u64,
+    /// Wall-clock time since the render started.
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    /// Fraction of the render that has been completed, in the range `[0, 1]`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use ray_tracing::camera::Progress;
+    ///
+    /// let progress = Progress {
+    ///     rows_completed: 1,
+    ///     total_rows: 4,
+    ///     passes_completed: 0,
+    ///     total_passes: 1,
+    ///     samples_completed: 25,
+    ///     total_samples: 100,
+    ///     elapsed: Duration::from_secs(1),
+    /// };
+    /// assert_eq!(progress.fraction(), 0.25);
+    /// ```
+    pub fn fraction(&self) -> f32 {
+        if self.total_samples == 0 {
+            1.0
+        } else {
+            self.samples_completed as f32 / self.total_samples as f32
+        }
+    }
+
+    /// Estimates the time left to finish the render by extrapolating the elapsed time.
+    ///
+    /// # Returns
+    /// `None` if no samples have been completed yet, so no estimate can be made.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use ray_tracing::camera::Progress;
+    ///
+    /// let progress = Progress {
+    ///     rows_completed: 1,
+    ///     total_rows: 4,
+    ///     passes_completed: 0,
+    ///     total_passes: 1,
+    ///     samples_completed: 25,
+    ///     total_samples: 100,
+    ///     elapsed: Duration::from_secs(1),
+    /// };
+    /// assert_eq!(progress.estimated_remaining(), Some(Duration::from_secs(3)));
+    /// ```
+    pub fn estimated_remaining(&self) -> Option<Duration> {
+        if self.samples_completed == 0 {
+            return None;
+        }
+        let remaining = self.total_samples.saturating_sub(self.samples_completed);
+        Some(
+            self.elapsed
+                .mul_f64(remaining as f64 / self.samples_completed as f64),
+        )
+    }
+}
+
+/// Receives progress events while the camera renders.
+///
+/// All methods have empty default implementations so an observer only needs to implement the
+/// events it is interested in.
+pub trait RenderObserver {
+    /// Called each time a row of the image has been rendered. Progressive renders call this once
+    /// per row in every pass.
+    #[allow(unused_variables)]
+    fn on_progress(&mut self, progress: &Progress) {}
+
+    /// Called each time a progressive render completes a sample pass over the whole image.
+    ///
+    /// The film holds every sample accumulated so far and can be resolved into a preview with
+    /// [`Film::snapshot`].
+    #[allow(unused_variables)]
+    fn on_pass(&mut self, progress: &Progress, film: &Film) {}
+
+    /// Called once when the render stops, either because it completed or it was cancelled.
+    #[allow(unused_variables)]
+    fn on_finish(&mut self, progress: &Progress, cancelled: bool) {}
+}
+
+/// An observer that ignores every event.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SilentObserver;
+
+impl RenderObserver for SilentObserver {}
+
+/// An observer that reports the remaining scanlines on stderr.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StderrObserver;
+
+impl RenderObserver for StderrObserver {
+    fn on_progress(&mut self, progress: &Progress) {
+        eprintln!(
+            "Scanlines remaining: {}",
+            progress.total_rows - progress.rows_completed
+        );
+    }
+
+    fn on_pass(&mut self, progress: &Progress, _film: &Film) {
+        match progress.total_passes {
+            0 => eprintln!("Pass {} complete.", progress.passes_completed),
+            total => eprintln!("Pass {} of {total} complete.", progress.passes_completed),
+        }
+    }
+
+    fn on_finish(&mut self, _progress: &Progress, cancelled: bool) {
+        if cancelled {
+            eprintln!("Cancelled.");
+        } else {
+            eprintln!("Done.");
+        }
+    }
+}
+
+/// A token used to request that a render stops early.
+///
+/// Clones share the same underlying flag, so one clone can be handed to the render while another
+/// is kept, for example by a UI thread, to cancel it.
+#[derive(Debug, Default, Clone)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a new token that has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation of every render using this token or one of its clones.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::camera::CancellationToken;
+    ///
+    /// let token = CancellationToken::new();
+    /// let shared = token.clone();
+    /// assert!(!token.is_cancelled());
+    /// shared.cancel();
+    /// assert!(token.is_cancelled());
+    /// ```
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Checks whether cancellation has been requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{aov::AovBuffers, film::Film, hittable::Hittable, image::Image, sampler::Sampler};
+
+use super::Camera;
+
+/// A render that traces one sample per pixel over the whole image, or the camera's region, in
+/// each pass.
+///
+/// Samples are accumulated into a [`Film`], so a noisy preview of the image is available after
+/// the first pass and keeps improving with every pass. Created with [`Camera::progressive`].
+pub struct ProgressiveRender<'a, H: Hittable> {
+    /// The camera generating rays.
+    camera: &'a Camera,
+    /// The scene being rendered.
+    world: &'a H,
+    /// Samples accumulated so far.
+    film: Film,
+    /// Auxiliary outputs accumulated so far, if the camera records them.
+    aovs: Option<AovBuffers>,
+    /// This render's copy of the camera's sampler.
+    sampler: Box<dyn Sampler>,
+    /// Number of completed passes.
+    passes: u32,
+}
+
+impl<'a, H: Hittable> ProgressiveRender<'a, H> {
+    pub(super) fn new(camera: &'a Camera, world: &'a H) -> Self {
+        Self {
+            camera,
+            world,
+            film: Film::with_filter(camera.region.width, camera.region.height, camera.filter),
+            aovs: camera.new_aovs(),
+            sampler: camera.sampler.clone(),
+            passes: 0,
+        }
+    }
+
+    /// Traces one more sample for every pixel of the image.
+    pub fn render_pass(&mut self) {
+        self.render_pass_with(|| false, u64::MAX, |_, _, _| true, |_, _| ());
+    }
+
+    /// Traces one more sample for the selected pixels, checking whether to stop before each
+    /// pixel.
+    ///
+    /// # Parameters
+    /// - `stop`: Stops the pass early when it returns `true`, for example once the render is
+    ///   cancelled. Samples already traced are kept.
+    /// - `limit`: Most samples to trace in this pass. If fewer than the selected pixels, the
+    ///   samples are spread evenly over the selected pixels rather than given to the first ones.
+    /// - `select`: Decides from the film before the pass whether the pixel at column `i` and row
+    ///   `j` receives a sample in this pass.
+    /// - `on_row`: Called after each row with the number of rows finished and the number of
+    ///   samples traced in this pass.
+    ///
+    /// # Returns
+    /// The number of samples traced, or `None` if the pass was stopped.
+    pub(super) fn render_pass_with(
+        &mut self,
+        stop: impl Fn() -> bool,
+        limit: u64,
+        mut select: impl FnMut(&Film, u32, u32) -> bool,
+        mut on_row: impl FnMut(u32, u64),
+    ) -> Option<u64> {
+        let camera = self.camera;
+        let region = camera.region;
+        let scene = camera.scene(self.world);
+        let selected: Vec<bool> = (0..region.height)
+            .flat_map(|j| (0..region.width).map(move |i| (i, j)))
+            .map(|(i, j)| select(&self.film, i, j))
+            .collect();
+        // When the limit can't cover every selected pixel, the nth selected pixel only gets a
+        // sample if the first n pixels' share of the limit, rounded up, grew with it. The samples
+        // are then spread over the whole region instead of filling its top rows.
+        let count = selected.iter().filter(|&&s| s).count() as u128;
+        let share = |n: u128| (n * limit as u128).div_ceil(count);
+        let mut seen = 0;
+        let mut traced = 0;
+        for j in 0..region.height {
+            for i in 0..region.width {
+                if stop() {
+                    return None;
+                }
+                if !selected[(j * region.width + i) as usize] {
+                    continue;
+                }
+                seen += 1;
+                if count > limit as u128 && share(seen) == share(seen - 1) {
+                    continue;
+                }
+                let sampler = self.sampler.as_mut();
+                let sample_index = self.film.sample_count(i, j);
+                let (r, offset) = camera.get_ray(region.x + i, region.y + j, sample_index, sampler);
+                if let Some(aovs) = &mut self.aovs {
+                    aovs.add_sample(i, j, &r, &scene);
+                }
+                let colour = camera.integrator.radiance(&r, &scene, sampler);
+                self.film.add_sample_at(i, j, offset, colour);
+                traced += 1;
+            }
+            on_row(j + 1, traced);
+        }
+        if traced > 0 {
+            // Pixels around the region get one sample per pass, however many their
+            // neighbours inside the region get.
+            let margin = self.passes..self.passes + 1;
+            let sampler = self.sampler.as_mut();
+            if !camera.trace_margin(&scene, &mut self.film, sampler, margin, &stop) {
+                return None;
+            }
+        }
+        self.passes += 1;
+        Some(traced)
+    }
+
+    /// Number of passes completed so far. Unless only some pixels were selected in a pass, this is
+    /// also the number of samples per pixel.
+    pub fn passes(&self) -> u32 {
+        self.passes
+    }
+
+    /// The samples accumulated so far.
+    pub fn film(&self) -> &Film {
+        &self.film
+    }
+
+    /// Consumes the render, returning the samples accumulated so far.
+    pub fn into_film(self) -> Film {
+        self.film
+    }
+
+    /// The auxiliary outputs accumulated so far, if the camera records them.
+    pub fn aovs(&self) -> Option<&AovBuffers> {
+        self.aovs.as_ref()
+    }
+
+    /// Consumes the render, returning the samples and auxiliary outputs accumulated so far.
+    pub(super) fn into_buffers(self) -> (Film, Option<AovBuffers>) {
+        (self.film, self.aovs)
+    }
+
+    /// Resolves the samples accumulated so far into an image.
+    pub fn snapshot(&self) -> Image {
+        self.film.snapshot()
+    }
+}
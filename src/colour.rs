@@ -110,6 +110,32 @@ impl Colour {
         }
     }
 
+    /// Relative luminance of the colour, using the Rec. 709 channel weights.
+    ///
+    /// # Returns
+    /// The luminance of the linear colour.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::Colour;
+    /// assert_eq!(Colour::new(0.0, 0.0, 0.0).luminance(), 0.0);
+    /// assert!((Colour::new(1.0, 1.0, 1.0).luminance() - 1.0).abs() < 1e-6);
+    /// ```
+    pub fn luminance(&self) -> f32 {
+        0.2126 * self.r() + 0.7152 * self.g() + 0.0722 * self.b()
+    }
+
+    /// The largest of the red, green and blue components.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::Colour;
+    /// assert_eq!(Colour::new(0.1, 0.7, 0.3).max_component(), 0.7);
+    /// ```
+    pub fn max_component(&self) -> f32 {
+        self.0.max_element()
+    }
+
     /// Converts a linear colour component to a gamma-corrected component.
     ///
     /// # Parameters
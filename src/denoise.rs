@@ -0,0 +1,342 @@
+use crate::{aov::AovBuffers, film::Film, image::Image, Colour, Vec3};
+
+/// Weights of the 5-tap B3 spline filter the wavelet transform is built from.
+const KERNEL: [f32; 5] = [1.0 / 16.0, 1.0 / 4.0, 3.0 / 8.0, 1.0 / 4.0, 1.0 / 16.0];
+
+/// Removes noise from a rendered image with an edge-avoiding à-trous wavelet filter, as used by
+/// spatiotemporal variance-guided filtering (SVGF).
+///
+/// Each iteration blurs the image with a 5×5 filter whose taps are spread twice as far apart as
+/// in the previous iteration, so a few iterations cover a wide area cheaply. The weight of each
+/// tap is reduced where the normal or albedo differs from the pixel being filtered, which keeps
+/// geometric and material edges sharp, and where the colour differs by more than the pixel's
+/// estimated noise, which keeps lighting detail such as shadow edges.
+///
+/// Denoising runs on the CPU and depends only on its input, so the same render always gives the
+/// same result.
+///
+/// # Example
+/// ```
+/// use ray_tracing::{camera::CameraBuilder, denoise::Denoiser};
+///
+/// let cam = CameraBuilder::default()
+///     .samples_per_pixel(16)
+///     .denoiser(Denoiser::default().iterations(4))
+///     .build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Denoiser {
+    /// Number of filter iterations.
+    iterations: u32,
+    /// How many standard deviations of noise a colour difference may be before it is treated
+    /// as an edge.
+    colour_sigma: f32,
+    /// Exponent sharpening the normal edge weight.
+    normal_power: f32,
+    /// Albedo difference that reduces the weight of a tap by a factor of e.
+    albedo_sigma: f32,
+}
+
+impl Default for Denoiser {
+    fn default() -> Self {
+        Self {
+            iterations: 5,
+            colour_sigma: 4.0,
+            normal_power: 128.0,
+            albedo_sigma: 0.1,
+        }
+    }
+}
+
+impl Denoiser {
+    /// Most filter iterations, whose filter already reaches over 100,000 pixels.
+    pub const MAX_ITERATIONS: u32 = 16;
+
+    /// Number of filter iterations. Each iteration doubles the radius of the filter, which
+    /// reaches `2^(iterations + 1) - 2` pixels. Defaults to 5, and is limited to
+    /// [`Denoiser::MAX_ITERATIONS`].
+    pub fn iterations(self, iterations: u32) -> Self {
+        Self {
+            iterations: iterations.min(Self::MAX_ITERATIONS),
+            ..self
+        }
+    }
+
+    /// How many standard deviations of a pixel's estimated noise a neighbour's colour may differ
+    /// by before it is treated as an edge. Larger values blur more. Defaults to 4.
+    pub fn colour_sigma(self, colour_sigma: f32) -> Self {
+        Self {
+            colour_sigma,
+            ..self
+        }
+    }
+
+    /// Exponent applied to the cosine between neighbouring normals; larger values stop the
+    /// filter at gentler creases. Defaults to 128.
+    pub fn normal_power(self, normal_power: f32) -> Self {
+        Self {
+            normal_power,
+            ..self
+        }
+    }
+
+    /// Albedo difference over which neighbours are treated as a different material. Defaults to
+    /// 0.1.
+    pub fn albedo_sigma(self, albedo_sigma: f32) -> Self {
+        Self {
+            albedo_sigma,
+            ..self
+        }
+    }
+
+    /// Denoises the image resolved from `film`, using the normals and albedo in `aovs` to find
+    /// edges and the film's per-pixel variance to estimate the noise.
+    ///
+    /// # Panics
+    /// Panics if `film` and `aovs` have different dimensions.
+    ///
+    /// # Returns
+    /// The denoised image.
+    pub fn denoise(&self, film: &Film, aovs: &AovBuffers) -> Image {
+        let (width, height) = (film.width(), film.height());
+        assert!(width == aovs.width() && height == aovs.height());
+
+        let mut guides = Vec::with_capacity(width as usize * height as usize);
+        let mut colour = Vec::with_capacity(guides.capacity());
+        let mut variance = Vec::with_capacity(guides.capacity());
+        for j in 0..height {
+            for i in 0..width {
+                guides.push(Guide {
+                    normal: aovs.normal(i, j).normalize_or_zero(),
+                    albedo: aovs.albedo(i, j),
+                    hit: aovs.depth(i, j).is_finite(),
+                });
+                colour.push(film.pixel(i, j));
+                // The variance of the pixel's mean, rather than of its individual samples.
+                variance.push(film.variance(i, j) / film.sample_count(i, j).max(1) as f32);
+            }
+        }
+
+        for iteration in 0..self.iterations {
+            let step = 1 << iteration;
+            // Smooth the variance estimate, which is itself noisy at low sample counts.
+            let noise: Vec<f32> = blur_3x3(&variance, width, height)
+                .into_iter()
+                .map(f32::sqrt)
+                .collect();
+            let mut filtered_colour = colour.clone();
+            let mut filtered_variance = variance.clone();
+
+            for j in 0..height as i32 {
+                for i in 0..width as i32 {
+                    let p = (j * width as i32 + i) as usize;
+                    let luminance = colour[p].luminance();
+                    let luminance_scale = self.colour_sigma * noise[p] + 1e-4;
+                    let mut weight_sum = 0.0;
+                    let mut colour_sum = Colour::default();
+                    let mut variance_sum = 0.0;
+
+                    for (dy, ky) in KERNEL.iter().enumerate() {
+                        for (dx, kx) in KERNEL.iter().enumerate() {
+                            let qi = i + (dx as i32 - 2) * step;
+                            let qj = j + (dy as i32 - 2) * step;
+                            if qi < 0 || qj < 0 || qi >= width as i32 || qj >= height as i32 {
+                                continue;
+                            }
+                            let q = (qj * width as i32 + qi) as usize;
+                            let colour_weight = (-(colour[q].luminance() - luminance).abs()
+                                / luminance_scale)
+                                .exp();
+                            let weight =
+                                kx * ky * colour_weight * self.edge_weight(&guides[p], &guides[q]);
+                            weight_sum += weight;
+                            colour_sum += weight * colour[q];
+                            variance_sum += weight * weight * variance[q];
+                        }
+                    }
+
+                    // The pixel itself always has a positive weight.
+                    filtered_colour[p] = colour_sum * (1.0 / weight_sum);
+                    filtered_variance[p] = variance_sum / (weight_sum * weight_sum);
+                }
+            }
+            colour = filtered_colour;
+            variance = filtered_variance;
+        }
+
+        let mut image = Image::new(width, height);
+        for j in 0..height {
+            for i in 0..width {
+                image.set(i, j, colour[(j * width + i) as usize]);
+            }
+        }
+        image
+    }
+
+    /// How much a neighbour with guides `q` is trusted to show the same surface as a pixel with
+    /// guides `p`.
+    fn edge_weight(&self, p: &Guide, q: &Guide) -> f32 {
+        if p.hit != q.hit {
+            return 0.0;
+        }
+        let normal_weight = if p.hit {
+            p.normal.dot(q.normal).max(0.0).powf(self.normal_power)
+        } else {
+            1.0
+        };
+        let albedo_difference = p.albedo - q.albedo;
+        let albedo_difference = Vec3::new(
+            albedo_difference.r(),
+            albedo_difference.g(),
+            albedo_difference.b(),
+        )
+        .length();
+        normal_weight * (-albedo_difference / self.albedo_sigma).exp()
+    }
+}
+
+/// The auxiliary outputs of a pixel used to find edges.
+struct Guide {
+    /// Unit normal of the surface seen in the pixel.
+    normal: Vec3,
+    /// Albedo of the surface seen in the pixel.
+    albedo: Colour,
+    /// Whether the pixel sees any surface.
+    hit: bool,
+}
+
+/// Blurs `values`, an image of the given dimensions in row-major order, with a 3×3 Gaussian.
+fn blur_3x3(values: &[f32], width: u32, height: u32) -> Vec<f32> {
+    const WEIGHTS: [f32; 3] = [0.25, 0.5, 0.25];
+    let mut blurred = vec![0.0; values.len()];
+    for j in 0..height as i32 {
+        for i in 0..width as i32 {
+            let mut sum = 0.0;
+            let mut weight_sum = 0.0;
+            for (dy, wy) in WEIGHTS.iter().enumerate() {
+                for (dx, wx) in WEIGHTS.iter().enumerate() {
+                    let qi = i + dx as i32 - 1;
+                    let qj = j + dy as i32 - 1;
+                    if qi < 0 || qj < 0 || qi >= width as i32 || qj >= height as i32 {
+                        continue;
+                    }
+                    sum += wx * wy * values[(qj * width as i32 + qi) as usize];
+                    weight_sum += wx * wy;
+                }
+            }
+            blurred[(j * width as i32 + i) as usize] = sum / weight_sum;
+        }
+    }
+    blurred
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hittable::HittableList, integrator::Scene, material::Lambertian, Point3, Quad, Ray,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::rc::Rc;
+
+    /// A film of noisy samples of a wall whose left half is dark grey and right half light grey,
+    /// with AOVs seen from straight in front of it.
+    fn noisy_wall(width: u32, height: u32) -> (Film, AovBuffers) {
+        let dark = Rc::new(Lambertian::new(&Colour::new(0.2, 0.2, 0.2)));
+        let light = Rc::new(Lambertian::new(&Colour::new(0.8, 0.8, 0.8)));
+        let size = Vec3::new(width as f32 / 2.0, 0.0, 0.0);
+        let up = Vec3::new(0.0, height as f32, 0.0);
+        let world: HittableList = crate::hittable_list![
+            Rc::new(Quad::new(Point3::new(0.0, 0.0, -1.0), size, up, dark)),
+            Rc::new(Quad::new(Point3::new(size.x, 0.0, -1.0), size, up, light)),
+        ];
+        let scene = Scene {
+            world: &world,
+            lights: None,
+            background: None,
+        };
+
+        let mut film = Film::new(width, height);
+        let mut aovs = AovBuffers::new(width, height);
+        let mut rng = StdRng::seed_from_u64(1);
+        for j in 0..height {
+            for i in 0..width {
+                let r = Ray::new(
+                    Point3::new(i as f32 + 0.5, (height - j) as f32 - 0.5, 0.0),
+                    -Vec3::Z,
+                );
+                let albedo = if i < width / 2 { 0.2 } else { 0.8 };
+                for _ in 0..8 {
+                    // Each sample is the albedo or nothing, with the mean being the albedo.
+                    let value = if rng.random::<f32>() < 0.5 {
+                        2.0 * albedo
+                    } else {
+                        0.0
+                    };
+                    film.add_sample(i, j, Colour::new(value, value, value));
+                    aovs.add_sample(i, j, &r, &scene);
+                }
+            }
+        }
+        (film, aovs)
+    }
+
+    /// Root mean square difference between the luminance of `image` and the wall's albedo, over
+    /// the columns in `columns`.
+    fn error(image: &Image, columns: std::ops::Range<u32>) -> f32 {
+        let mut sum = 0.0;
+        let mut n = 0;
+        for j in 0..image.height() {
+            for i in columns.clone() {
+                let expected = if i < image.width() / 2 { 0.2 } else { 0.8 };
+                sum += (image.get(i, j).luminance() - expected).powi(2);
+                n += 1;
+            }
+        }
+        (sum / n as f32).sqrt()
+    }
+
+    #[test]
+    fn reduces_noise_and_keeps_edges() {
+        let (film, aovs) = noisy_wall(32, 16);
+        let noisy = film.snapshot();
+        let denoised = Denoiser::default().denoise(&film, &aovs);
+
+        assert!(error(&denoised, 0..32) < error(&noisy, 0..32) / 3.0);
+        // The columns on either side of the edge are not blurred into each other.
+        assert!(error(&denoised, 15..17) < error(&noisy, 15..17) / 3.0);
+    }
+
+    #[test]
+    fn is_deterministic() {
+        let (film, aovs) = noisy_wall(16, 8);
+        let denoiser = Denoiser::default();
+        assert_eq!(
+            denoiser.denoise(&film, &aovs),
+            denoiser.denoise(&film, &aovs)
+        );
+    }
+
+    #[test]
+    fn limits_iterations() {
+        let denoiser = Denoiser::default().iterations(40);
+        assert_eq!(denoiser.iterations, Denoiser::MAX_ITERATIONS);
+        let (film, aovs) = noisy_wall(8, 4);
+        assert_eq!(denoiser.denoise(&film, &aovs).width(), 8);
+    }
+
+    #[test]
+    fn keeps_noise_free_images() {
+        let mut film = Film::new(4, 4);
+        let aovs = AovBuffers::new(4, 4);
+        for j in 0..4 {
+            for i in 0..4 {
+                film.add_sample(i, j, Colour::new(0.5, 0.25, 1.0));
+            }
+        }
+        let denoised = Denoiser::default().denoise(&film, &aovs);
+        for (a, b) in denoised.pixels().iter().zip(film.snapshot().pixels()) {
+            assert!((*a - *b).luminance().abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use crate::{filter::Filter, image::Image, Colour, Vec2};
+
+/// A floating point buffer that accumulates colour samples for each pixel.
+///
+/// Samples can be added in any order and any number of times per pixel. Each sample is weighted
+/// by the film's reconstruction [`Filter`] for every pixel within the filter's radius, and the
+/// resolved colour of a pixel is the weighted average of the samples that reached it. With the
+/// default box filter this is the plain average of the samples taken in the pixel.
+///
+/// The film also keeps a running mean and variance of the luminance of the samples taken in each
+/// pixel, which is used to estimate how noisy the pixel still is.
+#[derive(Debug, Clone)]
+pub struct Film {
+    /// Film width in pixels.
+    width: u32,
+    /// Film height in pixels.
+    height: u32,
+    /// Filter weighting the samples.
+    filter: Filter,
+    /// Sum of the weighted colour samples for each pixel.
+    sum: Vec<Colour>,
+    /// Sum of the sample weights for each pixel.
+    weight: Vec<f32>,
+    /// Count of samples taken in each pixel.
+    count: Vec<u32>,
+    /// Running mean of the sample luminance of each pixel.
+    luminance_mean: Vec<f32>,
+    /// Running sum of squared differences from the luminance mean of each pixel.
+    luminance_m2: Vec<f32>,
+}
+
+impl Film {
+    /// Creates a new empty film with the given dimensions and a box filter.
+    ///
+    /// # Parameters
+    /// - `width`: The film width in pixels.
+    /// - `height`: The film height in pixels.
+    ///
+    /// # Returns
+    /// A new `Film` with no samples.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self::with_filter(width, height, Filter::default())
+    }
+
+    /// Creates a new empty film with the given dimensions and reconstruction filter.
+    ///
+    /// # Parameters
+    /// - `width`: The film width in pixels.
+    /// - `height`: The film height in pixels.
+    /// - `filter`: The filter weighting each sample's contribution to nearby pixels.
+    ///
+    /// # Returns
+    /// A new `Film` with no samples.
+    pub fn with_filter(width: u32, height: u32, filter: Filter) -> Self {
+        let len = width as usize * height as usize;
+        Self {
+            width,
+            height,
+            filter,
+            sum: vec![Colour::default(); len],
+            weight: vec![0.0; len],
+            count: vec![0; len],
+            luminance_mean: vec![0.0; len],
+            luminance_m2: vec![0.0; len],
+        }
+    }
+
+    /// Film width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Film height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The filter weighting the samples.
+    pub fn filter(&self) -> Filter {
+        self.filter
+    }
+
+    /// Adds a colour sample taken at the centre of the pixel at column `i` and row `j`.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{film::Film, Colour};
+    /// let mut film = Film::new(2, 2);
+    /// film.add_sample(1, 1, Colour::new(1.0, 0.0, 0.0));
+    /// film.add_sample(1, 1, Colour::new(0.0, 1.0, 0.0));
+    /// assert_eq!(film.sample_count(1, 1), 2);
+    /// assert_eq!(film.pixel(1, 1), Colour::new(0.5, 0.5, 0.0));
+    /// ```
+    pub fn add_sample(&mut self, i: u32, j: u32, colour: Colour) {
+        self.add_sample_at(i, j, Vec2::ZERO, colour);
+    }
+
+    /// Adds a colour sample taken in the pixel at column `i` and row `j`, `offset` pixels from
+    /// its centre.
+    ///
+    /// The sample is added to every pixel within the filter's radius of where it was taken,
+    /// weighted by the filter, but only counts towards the sample count and noise estimate of
+    /// the pixel it was taken in.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{film::Film, filter::Filter, Colour, Vec2};
+    /// let mut film = Film::with_filter(3, 1, Filter::Tent { radius: 1.5 });
+    /// film.add_sample_at(1, 0, Vec2::ZERO, Colour::new(1.0, 1.0, 1.0));
+    /// assert_eq!(film.sample_count(0, 0), 0);
+    /// assert_eq!(film.pixel(0, 0), Colour::new(1.0, 1.0, 1.0));
+    /// ```
+    pub fn add_sample_at(&mut self, i: u32, j: u32, offset: Vec2, colour: Colour) {
+        self.splat(i as f32 + 0.5 + offset.x, j as f32 + 0.5 + offset.y, colour);
+
+        let index = self.index(i, j);
+        self.count[index] += 1;
+
+        // Welford's online update of the luminance mean and variance.
+        let luminance = colour.luminance();
+        let delta = luminance - self.luminance_mean[index];
+        self.luminance_mean[index] += delta / self.count[index] as f32;
+        self.luminance_m2[index] += delta * (luminance - self.luminance_mean[index]);
+    }
+
+    /// Adds a colour sample taken outside the film, in the pixel at column `i` and row `j`
+    /// counted from the film's top left pixel, `offset` pixels from its centre.
+    ///
+    /// The sample is added to the pixels of the film within the filter's radius, weighted by the
+    /// filter, but does not count as a sample of any pixel. Splatting the samples of pixels
+    /// around a film that covers part of a frame gives its edge pixels the same value as in a
+    /// film covering the whole frame.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{film::Film, filter::Filter, Colour, Vec2};
+    /// let mut film = Film::with_filter(2, 1, Filter::Tent { radius: 1.5 });
+    /// film.add_splat(-1, 0, Vec2::ZERO, Colour::new(1.0, 1.0, 1.0));
+    /// assert_eq!(film.sample_count(0, 0), 0);
+    /// assert_eq!(film.pixel(0, 0), Colour::new(1.0, 1.0, 1.0));
+    /// ```
+    pub fn add_splat(&mut self, i: i32, j: i32, offset: Vec2, colour: Colour) {
+        self.splat(i as f32 + 0.5 + offset.x, j as f32 + 0.5 + offset.y, colour);
+    }
+
+    /// Adds a colour sample taken at `x`, `y` in pixels from the film's top left corner to every
+    /// pixel whose centre is within the filter radius, weighted by the filter.
+    fn splat(&mut self, x: f32, y: f32, colour: Colour) {
+        let radius = self.filter.radius();
+        let x0 = (x - 0.5 - radius).ceil().max(0.0) as u32;
+        let x1 = (x - 0.5 + radius).floor().min(self.width as f32 - 1.0);
+        let y0 = (y - 0.5 - radius).ceil().max(0.0) as u32;
+        let y1 = (y - 0.5 + radius).floor().min(self.height as f32 - 1.0);
+        if x1 >= 0.0 && y1 >= 0.0 {
+            for py in y0..=y1 as u32 {
+                for px in x0..=x1 as u32 {
+                    let weight = self
+                        .filter
+                        .evaluate(x - (px as f32 + 0.5), y - (py as f32 + 0.5));
+                    if weight != 0.0 {
+                        let index = self.index(px, py);
+                        self.sum[index] += weight * colour;
+                        self.weight[index] += weight;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Number of samples added to the pixel at column `i` and row `j`.
+    pub fn sample_count(&self, i: u32, j: u32) -> u32 {
+        self.count[self.index(i, j)]
+    }
+
+    /// The smallest number of samples added to any pixel.
+    pub fn min_sample_count(&self) -> u32 {
+        self.count.iter().copied().min().unwrap_or(0)
+    }
+
+    /// Unbiased sample variance of the luminance of the samples added to the pixel at column `i`
+    /// and row `j`, or zero if it has fewer than two samples.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{film::Film, Colour};
+    /// let mut film = Film::new(1, 1);
+    /// film.add_sample(0, 0, Colour::new(0.0, 0.0, 0.0));
+    /// film.add_sample(0, 0, Colour::new(2.0, 2.0, 2.0));
+    /// assert!((film.variance(0, 0) - 2.0).abs() < 1e-5);
+    /// ```
+    pub fn variance(&self, i: u32, j: u32) -> f32 {
+        let index = self.index(i, j);
+        match self.count[index] {
+            0 | 1 => 0.0,
+            n => self.luminance_m2[index] / (n - 1) as f32,
+        }
+    }
+
+    /// Estimated relative error of the pixel at column `i` and row `j`.
+    ///
+    /// This is the standard error of the mean luminance divided by the mean luminance, so it
+    /// shrinks as more samples are added and is comparable between bright and dark pixels. Very
+    /// dark pixels are treated as having a luminance of at least `0.01` so that they are not
+    /// considered noisy forever. Pixels with fewer than two samples have an infinite error.
+    pub fn relative_error(&self, i: u32, j: u32) -> f32 {
+        let index = self.index(i, j);
+        let n = self.count[index];
+        if n < 2 {
+            return f32::INFINITY;
+        }
+        let standard_error = (self.variance(i, j) / n as f32).sqrt();
+        standard_error / self.luminance_mean[index].max(0.01)
+    }
+
+    /// The filter weighted average of the samples that reached the pixel at column `i` and row
+    /// `j`, or black if none have yet.
+    pub fn pixel(&self, i: u32, j: u32) -> Colour {
+        let index = self.index(i, j);
+        let weight = self.weight[index];
+        // Filters with negative lobes can leave a tiny total weight, which would blow up.
+        if weight.abs() < 1e-6 {
+            Colour::default()
+        } else {
+            self.sum[index] * (1.0 / weight)
+        }
+    }
+
+    /// Resolves the accumulated samples into an image.
+    ///
+    /// # Returns
+    /// An `Image` where each pixel is the average of its samples so far.
+    pub fn snapshot(&self) -> Image {
+        let mut image = Image::new(self.width, self.height);
+        for j in 0..self.height {
+            for i in 0..self.width {
+                image.set(i, j, self.pixel(i, j));
+            }
+        }
+        image
+    }
+
+    /// Visualises the number of samples taken per pixel as a heatmap.
+    ///
+    /// Pixels with the fewest samples are blue, shading through green to red for the pixels with
+    /// the most samples. Useful for checking where adaptive sampling spent its budget.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{film::Film, Colour};
+    /// let mut film = Film::new(2, 1);
+    /// film.add_sample(0, 0, Colour::default());
+    /// film.add_sample(1, 0, Colour::default());
+    /// film.add_sample(1, 0, Colour::default());
+    /// let heatmap = film.sample_heatmap();
+    /// assert_eq!(heatmap.get(0, 0), Colour::new(0.0, 0.0, 1.0));
+    /// assert_eq!(heatmap.get(1, 0), Colour::new(1.0, 0.0, 0.0));
+    /// ```
+    pub fn sample_heatmap(&self) -> Image {
+        let min = self.min_sample_count();
+        let max = self.count.iter().copied().max().unwrap_or(0);
+        let range = (max - min).max(1) as f32;
+
+        let mut image = Image::new(self.width, self.height);
+        for j in 0..self.height {
+            for i in 0..self.width {
+                let t = (self.sample_count(i, j) - min) as f32 / range;
+                let colour = if t < 0.5 {
+                    Colour::new(0.0, 2.0 * t, 1.0 - 2.0 * t)
+                } else {
+                    Colour::new(2.0 * t - 1.0, 2.0 - 2.0 * t, 0.0)
+                };
+                image.set(i, j, colour);
+            }
+        }
+        image
+    }
+
+    fn index(&self, i: u32, j: u32) -> usize {
+        assert!(i < self.width && j < self.height);
+        j as usize * self.width as usize + i as usize
+    }
+}
@@ -0,0 +1,183 @@
+use std::f32::consts::PI;
+
+/// A pixel reconstruction filter, weighting each sample by its distance from a pixel's centre.
+///
+/// A filter with a radius larger than half a pixel spreads each sample into the neighbouring
+/// pixels too, which smooths out aliasing along high-contrast edges. Every filter is separable,
+/// the product of a one-dimensional filter in `x` and in `y`, and is zero beyond its radius.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Filter {
+    /// Weights every sample within the radius equally. With a radius of half a pixel this is a
+    /// plain average of the samples taken in each pixel.
+    Box { radius: f32 },
+    /// Weight falls off linearly to zero at the radius.
+    Tent { radius: f32 },
+    /// A Gaussian with standard deviation `sigma`, shifted down so it reaches zero at the radius.
+    Gaussian { radius: f32, sigma: f32 },
+    /// The Mitchell-Netravali cubic filter, with the `b` and `c` parameters trading blurring
+    /// against ringing. It has small negative lobes which sharpen edges.
+    MitchellNetravali { radius: f32, b: f32, c: f32 },
+    /// A sinc filter windowed by a wider sinc with `tau` cycles. Sharp, but rings near edges.
+    Lanczos { radius: f32, tau: f32 },
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter::Box { radius: 0.5 }
+    }
+}
+
+impl Filter {
+    /// A Gaussian filter with the given radius and a standard deviation of half a pixel.
+    pub fn gaussian(radius: f32) -> Self {
+        Filter::Gaussian { radius, sigma: 0.5 }
+    }
+
+    /// A Mitchell-Netravali filter with the given radius and the recommended `b = c = 1/3`.
+    pub fn mitchell(radius: f32) -> Self {
+        Filter::MitchellNetravali {
+            radius,
+            b: 1.0 / 3.0,
+            c: 1.0 / 3.0,
+        }
+    }
+
+    /// A Lanczos filter with the given radius, windowed to the same radius.
+    pub fn lanczos(radius: f32) -> Self {
+        Filter::Lanczos {
+            radius,
+            tau: radius,
+        }
+    }
+
+    /// Distance from a pixel's centre, in pixels, beyond which the filter is zero.
+    pub fn radius(&self) -> f32 {
+        match *self {
+            Filter::Box { radius }
+            | Filter::Tent { radius }
+            | Filter::Gaussian { radius, .. }
+            | Filter::MitchellNetravali { radius, .. }
+            | Filter::Lanczos { radius, .. } => radius,
+        }
+    }
+
+    /// Weight of a sample offset by `x` and `y` pixels from a pixel's centre.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::filter::Filter;
+    ///
+    /// let filter = Filter::Tent { radius: 1.0 };
+    /// assert_eq!(filter.evaluate(0.0, 0.0), 1.0);
+    /// assert_eq!(filter.evaluate(0.5, 0.0), 0.5);
+    /// assert_eq!(filter.evaluate(1.0, 0.0), 0.0);
+    /// ```
+    pub fn evaluate(&self, x: f32, y: f32) -> f32 {
+        self.evaluate_1d(x) * self.evaluate_1d(y)
+    }
+
+    fn evaluate_1d(&self, x: f32) -> f32 {
+        match *self {
+            // Half open so that a sample on the border of two pixels only counts towards one.
+            Filter::Box { radius } => {
+                if (-radius..radius).contains(&x) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Filter::Tent { radius } => (radius - x.abs()).max(0.0),
+            Filter::Gaussian { radius, sigma } => {
+                let gaussian = |x: f32| (-x * x / (2.0 * sigma * sigma)).exp();
+                if x.abs() >= radius {
+                    0.0
+                } else {
+                    (gaussian(x) - gaussian(radius)).max(0.0)
+                }
+            }
+            Filter::MitchellNetravali { radius, b, c } => mitchell_1d(2.0 * x / radius, b, c),
+            Filter::Lanczos { radius, tau } => {
+                if x.abs() >= radius {
+                    0.0
+                } else {
+                    sinc(x) * sinc(x / tau)
+                }
+            }
+        }
+    }
+}
+
+/// The Mitchell-Netravali cubic, which is non-zero over `[-2, 2]`.
+fn mitchell_1d(x: f32, b: f32, c: f32) -> f32 {
+    let x = x.abs();
+    if x <= 1.0 {
+        ((12.0 - 9.0 * b - 6.0 * c) * x * x * x
+            + (-18.0 + 12.0 * b + 6.0 * c) * x * x
+            + (6.0 - 2.0 * b))
+            / 6.0
+    } else if x < 2.0 {
+        ((-b - 6.0 * c) * x * x * x
+            + (6.0 * b + 30.0 * c) * x * x
+            + (-12.0 * b - 48.0 * c) * x
+            + (8.0 * b + 24.0 * c))
+            / 6.0
+    } else {
+        0.0
+    }
+}
+
+/// The normalised sinc function, `sin(πx) / πx`.
+fn sinc(x: f32) -> f32 {
+    if x.abs() < 1e-5 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILTERS: [Filter; 5] = [
+        Filter::Box { radius: 0.5 },
+        Filter::Tent { radius: 1.5 },
+        Filter::Gaussian {
+            radius: 1.5,
+            sigma: 0.5,
+        },
+        Filter::MitchellNetravali {
+            radius: 2.0,
+            b: 1.0 / 3.0,
+            c: 1.0 / 3.0,
+        },
+        Filter::Lanczos {
+            radius: 3.0,
+            tau: 3.0,
+        },
+    ];
+
+    #[test]
+    fn filters_peak_at_centre() {
+        for filter in FILTERS {
+            let centre = filter.evaluate(0.0, 0.0);
+            assert!(centre > 0.0);
+            assert!(filter.evaluate(0.25, 0.1) <= centre);
+        }
+    }
+
+    #[test]
+    fn filters_vanish_beyond_radius() {
+        for filter in FILTERS {
+            let r = filter.radius();
+            assert_eq!(filter.evaluate(r, 0.0), 0.0);
+            assert_eq!(filter.evaluate(0.0, -r - 0.1), 0.0);
+        }
+    }
+
+    #[test]
+    fn mitchell_has_negative_lobes() {
+        let filter = Filter::mitchell(2.0);
+        assert!(filter.evaluate(1.5, 0.0) < 0.0);
+    }
+}
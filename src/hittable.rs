@@ -1,4 +1,4 @@
-use crate::{interval::Interval, material::Material, Ray};
+use crate::{interval::Interval, material::Material, Point3, Ray, Vec2, Vec3};
 use std::rc::Rc;
 
 mod hit_record;
@@ -7,7 +7,29 @@ mod hittable_list;
 pub use hit_record::HitRecord;
 pub use hittable_list::HittableList;
 
+impl std::fmt::Debug for dyn Hittable + '_ {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("dyn Hittable")
+    }
+}
+
 pub trait Hittable {
     fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool;
     fn mat(&self) -> Option<Rc<dyn Material>>;
+
+    /// The density, with respect to solid angle, of [`Hittable::random`] choosing `direction`
+    /// from `origin`. Zero if `direction` does not hit the object, or if the object does not
+    /// support sampling.
+    #[allow(unused_variables)]
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f32 {
+        0.0
+    }
+
+    /// Maps a uniform sample in `[0, 1)²` to a direction from `origin` towards the object, used
+    /// to send rays towards lights. Objects that do not support sampling return an arbitrary
+    /// direction, with a [`Hittable::pdf_value`] of zero.
+    #[allow(unused_variables)]
+    fn random(&self, origin: Point3, u: Vec2) -> Vec3 {
+        Vec3::X
+    }
 }
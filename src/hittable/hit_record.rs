@@ -8,6 +8,9 @@ pub struct HitRecord {
     pub mat: Option<Rc<dyn Material>>,
     pub t: f32,
     pub front_face: bool,
+    /// Index of the hit object in the outermost [`HittableList`](super::HittableList) containing
+    /// it, identifying the object in auxiliary outputs.
+    pub object_id: usize,
 }
 
 impl HitRecord {
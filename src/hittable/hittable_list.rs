@@ -1,4 +1,4 @@
-use crate::{hittable::Hittable, interval::Interval, material::Material, Ray};
+use crate::{hittable::Hittable, interval::Interval, material::Material, Point3, Ray, Vec2, Vec3};
 use std::rc::Rc;
 
 use super::HitRecord;
@@ -20,30 +20,59 @@ impl HittableList {
     pub fn len(&self) -> usize {
         self.objects.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
 }
 
 impl Hittable for HittableList {
     fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
-        let mut temp_rec = HitRecord::default();
-        temp_rec.mat = rec.mat.clone();
+        let mut temp_rec = HitRecord {
+            mat: rec.mat.clone(),
+            ..Default::default()
+        };
         let mut hit_anything = false;
         let mut closest_so_far = ray_t.max;
 
-        for object in self.objects.iter() {
+        for (index, object) in self.objects.iter().enumerate() {
             let ray_t = Interval::new(ray_t.min, closest_so_far);
             if object.hit(r, ray_t, &mut temp_rec) {
                 hit_anything = true;
                 closest_so_far = temp_rec.t;
                 std::mem::swap(rec, &mut temp_rec);
                 rec.mat = object.mat().clone();
+                rec.object_id = index;
             }
         }
-        return hit_anything;
+        hit_anything
     }
 
     fn mat(&self) -> Option<Rc<dyn Material>> {
         None
     }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f32 {
+        // Each object is chosen with the same probability by `random`.
+        let sum: f32 = self
+            .objects
+            .iter()
+            .map(|object| object.pdf_value(origin, direction))
+            .sum();
+        sum / self.objects.len().max(1) as f32
+    }
+
+    fn random(&self, origin: Point3, u: Vec2) -> Vec3 {
+        if self.objects.is_empty() {
+            return Vec3::X;
+        }
+        // Choose an object with the first dimension and reuse what is left of it.
+        let n = self.objects.len();
+        let scaled = u.x * n as f32;
+        let index = (scaled as usize).min(n - 1);
+        let u = Vec2::new((scaled - index as f32).min(1.0 - f32::EPSILON / 2.0), u.y);
+        self.objects[index].random(origin, u)
+    }
 }
 
 pub mod macros {
@@ -0,0 +1,222 @@
+use crate::Colour;
+use std::io::Write;
+
+/// A rectangle of pixels within an image, such as the part of the camera frame to render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    /// Column of the left edge of the region.
+    pub x: u32,
+    /// Row of the top edge of the region.
+    pub y: u32,
+    /// Region width in pixels.
+    pub width: u32,
+    /// Region height in pixels.
+    pub height: u32,
+}
+
+impl Region {
+    /// Creates a new region with its top left corner at column `x` and row `y`.
+    pub const fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Checks whether the pixel at column `i` and row `j` is inside the region.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::image::Region;
+    ///
+    /// let region = Region::new(2, 1, 3, 2);
+    /// assert!(region.contains(2, 1));
+    /// assert!(region.contains(4, 2));
+    /// assert!(!region.contains(5, 2));
+    /// assert!(!region.contains(2, 0));
+    /// ```
+    pub fn contains(&self, i: u32, j: u32) -> bool {
+        (self.x..self.x + self.width).contains(&i) && (self.y..self.y + self.height).contains(&j)
+    }
+
+    /// Returns the part of this region that overlaps `other`, which is empty if they do not
+    /// overlap.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::image::Region;
+    ///
+    /// let frame = Region::new(0, 0, 10, 10);
+    /// assert_eq!(Region::new(8, 2, 4, 4).intersect(&frame), Region::new(8, 2, 2, 4));
+    /// assert_eq!(Region::new(12, 2, 4, 4).intersect(&frame).width, 0);
+    /// ```
+    pub fn intersect(&self, other: &Region) -> Region {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+        Region::new(x, y, right.saturating_sub(x), bottom.saturating_sub(y))
+    }
+}
+
+/// An image held in memory as linear (not gamma corrected) colour values.
+///
+/// Pixels are stored in row-major order starting from the top left of the image, matching the
+/// order in which the camera traces them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    /// Image width in pixels.
+    width: u32,
+    /// Image height in pixels.
+    height: u32,
+    /// Pixel colours in row-major order.
+    pixels: Vec<Colour>,
+}
+
+impl Image {
+    /// Creates a new black image with the given dimensions.
+    ///
+    /// # Parameters
+    /// - `width`: The image width in pixels.
+    /// - `height`: The image height in pixels.
+    ///
+    /// # Returns
+    /// A new `Image` with every pixel set to black.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{image::Image, Colour};
+    /// let image = Image::new(4, 2);
+    /// assert_eq!(image.width(), 4);
+    /// assert_eq!(image.height(), 2);
+    /// assert_eq!(image.get(3, 1), Colour::new(0.0, 0.0, 0.0));
+    /// ```
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Colour::default(); width as usize * height as usize],
+        }
+    }
+
+    /// Image width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Image height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the pixel colours in row-major order.
+    pub fn pixels(&self) -> &[Colour] {
+        &self.pixels
+    }
+
+    /// Gets the colour of the pixel at column `i` and row `j`.
+    ///
+    /// # Panics
+    /// Panics if the pixel is outside of the image.
+    pub fn get(&self, i: u32, j: u32) -> Colour {
+        self.pixels[self.index(i, j)]
+    }
+
+    /// Sets the colour of the pixel at column `i` and row `j`.
+    ///
+    /// # Panics
+    /// Panics if the pixel is outside of the image.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{image::Image, Colour};
+    /// let mut image = Image::new(2, 2);
+    /// image.set(1, 0, Colour::new(1.0, 0.5, 0.0));
+    /// assert_eq!(image.get(1, 0), Colour::new(1.0, 0.5, 0.0));
+    /// ```
+    pub fn set(&mut self, i: u32, j: u32, colour: Colour) {
+        let index = self.index(i, j);
+        self.pixels[index] = colour;
+    }
+
+    /// Copies `other` into this image with its top left corner at column `x` and row `y`.
+    ///
+    /// Any part of `other` that falls outside of this image is ignored.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{image::Image, Colour};
+    /// let mut tile = Image::new(2, 2);
+    /// tile.set(0, 0, Colour::new(1.0, 0.0, 0.0));
+    /// let mut frame = Image::new(4, 4);
+    /// frame.paste(&tile, 3, 1);
+    /// assert_eq!(frame.get(3, 1), Colour::new(1.0, 0.0, 0.0));
+    /// ```
+    pub fn paste(&mut self, other: &Image, x: u32, y: u32) {
+        for j in 0..other.height.min(self.height.saturating_sub(y)) {
+            for i in 0..other.width.min(self.width.saturating_sub(x)) {
+                self.set(x + i, y + j, other.get(i, j));
+            }
+        }
+    }
+
+    /// Writes the image as a plain text (P3) PPM file, gamma correcting each pixel.
+    ///
+    /// # Parameters
+    /// - `out`: The writer to output the image to.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{image::Image, Colour};
+    /// let mut image = Image::new(1, 1);
+    /// image.set(0, 0, Colour::new(1.0, 1.0, 1.0));
+    /// let mut out = Vec::new();
+    /// image.write_ppm(&mut out).unwrap();
+    /// assert_eq!(String::from_utf8(out).unwrap(), "P3\n1 1\n255\n255 255 255\n");
+    /// ```
+    pub fn write_ppm(&self, mut out: impl Write) -> std::io::Result<()> {
+        writeln!(out, "P3\n{} {}\n255", self.width, self.height)?;
+        for pixel in self.pixels.iter() {
+            writeln!(out, "{pixel}")?;
+        }
+        Ok(())
+    }
+
+    /// Writes the image as a colour PFM (portable float map) file, keeping the linear floating
+    /// point values without gamma correction or clamping.
+    ///
+    /// # Parameters
+    /// - `out`: The writer to output the image to.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{image::Image, Colour};
+    /// let mut image = Image::new(1, 1);
+    /// image.set(0, 0, Colour::new(2.0, 0.5, -1.0));
+    /// let mut out = Vec::new();
+    /// image.write_pfm(&mut out).unwrap();
+    /// assert!(out.starts_with(b"PF\n1 1\n-1.0\n"));
+    /// assert_eq!(out[out.len() - 12..out.len() - 8], 2.0f32.to_le_bytes());
+    /// ```
+    pub fn write_pfm(&self, mut out: impl Write) -> std::io::Result<()> {
+        // A negative scale marks the data as little endian.
+        write!(out, "PF\n{} {}\n-1.0\n", self.width, self.height)?;
+        // Rows are stored from the bottom of the image to the top.
+        for j in (0..self.height).rev() {
+            for i in 0..self.width {
+                let pixel = self.get(i, j);
+                for channel in [pixel.r(), pixel.g(), pixel.b()] {
+                    out.write_all(&channel.to_le_bytes())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn index(&self, i: u32, j: u32) -> usize {
+        assert!(i < self.width && j < self.height);
+        j as usize * self.width as usize + i as usize
+    }
+}
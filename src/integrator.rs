@@ -0,0 +1,105 @@
+use crate::{
+    hittable::{HitRecord, Hittable},
+    interval::Interval,
+    pdf::Pdf,
+    sampler::Sampler,
+    Colour, Ray, Vec2,
+};
+
+mod ambient_occlusion;
+mod direct;
+mod mis;
+mod normals;
+mod path;
+
+pub use ambient_occlusion::AmbientOcclusionIntegrator;
+pub use direct::DirectLightingIntegrator;
+pub use mis::MisHeuristic;
+pub use normals::NormalsIntegrator;
+pub use path::PathIntegrator;
+
+/// Computes the light arriving at the camera along a camera ray.
+///
+/// The camera generates the rays and accumulates the results into its film; the integrator
+/// decides how light is transported through the scene. Besides physically based path tracing,
+/// integrators can show quick approximations or debugging views of the scene.
+pub trait Integrator: std::fmt::Debug {
+    /// Estimates the light arriving along the camera ray `r`, drawing any random choices from
+    /// `sampler`.
+    fn radiance(&self, r: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Colour;
+
+    /// Clones the integrator into a new box, so that cameras holding one can be cloned.
+    fn clone_box(&self) -> Box<dyn Integrator>;
+}
+
+impl Clone for Box<dyn Integrator> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// The scene an integrator traces rays through.
+#[derive(Debug, Clone, Copy)]
+pub struct Scene<'a> {
+    /// Every object in the scene.
+    pub world: &'a dyn Hittable,
+    /// Emissive objects to sample directly, if any.
+    pub lights: Option<&'a dyn Hittable>,
+    /// Colour of rays that escape the scene, or a sky gradient if not set.
+    pub background: Option<Colour>,
+}
+
+impl Scene<'_> {
+    /// Finds the closest hit of `r` with the world, ignoring hits right at its origin.
+    pub fn hit(&self, r: &Ray, rec: &mut HitRecord) -> bool {
+        self.world.hit(r, Interval::new(0.001, f32::INFINITY), rec)
+    }
+
+    /// The colour of a ray that escapes the scene.
+    pub fn background(&self, r: &Ray) -> Colour {
+        if let Some(background) = self.background {
+            return background;
+        }
+        let unit_direction = r.direction().normalize();
+        let a = 0.5 * (unit_direction.y + 1.0);
+        (1.0 - a) * Colour::new(1.0, 1.0, 1.0) + a * Colour::new(0.5, 0.7, 1.0)
+    }
+
+    /// Estimates the light arriving at the hit point `rec` directly from the lights, by sending a
+    /// shadow ray towards a point on the lights chosen with the sample `u`. The light found is
+    /// weighted with `heuristic` against finding it by sampling the material's density `pdf`.
+    ///
+    /// # Returns
+    /// The material's response towards the light, weighted by the heuristic over the light's
+    /// density, and the light emitted, or `None` if no light is found. They are kept apart so
+    /// they can be converted to spectra separately.
+    fn sample_light(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        pdf: &dyn Pdf,
+        heuristic: MisHeuristic,
+        u: Vec2,
+    ) -> Option<(Colour, Colour)> {
+        let lights = self.lights?;
+        let direction = lights.random(rec.p, u);
+        let light_pdf = lights.pdf_value(rec.p, direction);
+        if light_pdf <= 0.0 {
+            return None;
+        }
+
+        // Only light reaching the hit point unobstructed counts.
+        let shadow_ray = Ray::new(rec.p, direction);
+        let mut light_rec = HitRecord::default();
+        if !self.hit(&shadow_ray, &mut light_rec) {
+            return None;
+        }
+        let emitted = light_rec.mat.as_ref()?.emitted(&shadow_ray, &light_rec);
+        let mat = rec.mat.as_ref()?;
+        let weight = heuristic.weight(light_pdf, pdf.value(direction));
+        Some((
+            mat.eval(r_in, rec, &shadow_ray) * (weight / light_pdf),
+            emitted,
+        ))
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{
+    hittable::HitRecord, interval::Interval, sampler::sample_cosine_hemisphere, sampler::Sampler,
+    util::Onb, Colour, Ray,
+};
+
+use super::{Integrator, Scene};
+
+/// Shades the first surface each camera ray hits grey by the fraction of the hemisphere above it
+/// that is unoccluded, ignoring materials and lights. Rays that miss are black.
+///
+/// A quick way to check the layout of a scene: a few unshaded rays per sample are much cheaper
+/// than a full path trace, and show how objects sit relative to each other.
+///
+/// # Example
+/// ```
+/// use ray_tracing::{camera::CameraBuilder, integrator::AmbientOcclusionIntegrator};
+///
+/// let cam = CameraBuilder::default()
+///     .integrator(AmbientOcclusionIntegrator::new(0.5).samples(4))
+///     .build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmbientOcclusionIntegrator {
+    /// Distance within which objects occlude a surface.
+    radius: f32,
+    /// Occlusion rays cast for each camera ray.
+    samples: u32,
+}
+
+impl AmbientOcclusionIntegrator {
+    /// Creates a new ambient occlusion integrator where only objects within `radius` of a
+    /// surface occlude it, so that distant walls do not darken everything.
+    pub fn new(radius: f32) -> Self {
+        Self { radius, samples: 1 }
+    }
+
+    /// Occlusion rays cast from the first hit of each camera ray. More rays reduce the noise of
+    /// each sample. Defaults to 1.
+    pub fn samples(self, samples: u32) -> Self {
+        Self {
+            samples: samples.max(1),
+            ..self
+        }
+    }
+}
+
+impl Default for AmbientOcclusionIntegrator {
+    /// Ambient occlusion from objects at any distance.
+    fn default() -> Self {
+        Self::new(f32::INFINITY)
+    }
+}
+
+impl Integrator for AmbientOcclusionIntegrator {
+    fn radiance(&self, r: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Colour {
+        let mut rec = HitRecord::default();
+        if !scene.hit(r, &mut rec) {
+            return Colour::default();
+        }
+
+        // Cosine distributed rays weight the occluders by how directly they face the surface.
+        let uvw = Onb::new(rec.normal);
+        let ray_t = Interval::new(0.001, self.radius);
+        let mut unoccluded = 0;
+        for _ in 0..self.samples {
+            let direction = uvw.transform(sample_cosine_hemisphere(sampler.get_2d()));
+            let mut occluder = HitRecord::default();
+            if !scene
+                .world
+                .hit(&Ray::new(rec.p, direction), ray_t, &mut occluder)
+            {
+                unoccluded += 1;
+            }
+        }
+        let fraction = unoccluded as f32 / self.samples as f32;
+        Colour::new(fraction, fraction, fraction)
+    }
+
+    fn clone_box(&self) -> Box<dyn Integrator> {
+        Box::new(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hittable::HittableList, material::Lambertian, sampler::IndependentSampler, Point3, Sphere,
+        Vec3,
+    };
+    use std::rc::Rc;
+
+    /// A unit sphere resting on a floor at `y = 0`.
+    fn scene_objects() -> HittableList {
+        let grey = Rc::new(Lambertian::new(&Colour::new(0.5, 0.5, 0.5)));
+        crate::hittable_list![
+            Rc::new(Sphere::new(Point3::new(0.0, 1.0, 0.0), 1.0, grey.clone())),
+            Rc::new(Sphere::new(Point3::new(0.0, -1000.0, 0.0), 1000.0, grey)),
+        ]
+    }
+
+    /// Ambient occlusion of the floor point next to where the sphere touches it, seen from above.
+    fn occlusion_near_contact(integrator: AmbientOcclusionIntegrator) -> f32 {
+        let world = scene_objects();
+        let scene = Scene {
+            world: &world,
+            lights: None,
+            background: None,
+        };
+        let r = Ray::new(Point3::new(1.2, 5.0, 0.0), -Vec3::Y);
+        integrator
+            .radiance(&r, &scene, &mut IndependentSampler)
+            .luminance()
+    }
+
+    #[test]
+    fn nearby_objects_occlude() {
+        let unoccluded =
+            occlusion_near_contact(AmbientOcclusionIntegrator::default().samples(4096));
+        // The sphere covers a large part of the sky seen from right next to it.
+        assert!(unoccluded > 0.3 && unoccluded < 0.9);
+    }
+
+    #[test]
+    fn objects_beyond_radius_do_not_occlude() {
+        let integrator = AmbientOcclusionIntegrator::new(0.01).samples(64);
+        assert!((occlusion_near_contact(integrator) - 1.0).abs() < 1e-5);
+    }
+}
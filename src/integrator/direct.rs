@@ -0,0 +1,50 @@
+use crate::{sampler::Sampler, Colour, Ray};
+
+use super::{Integrator, MisHeuristic, PathIntegrator, Scene};
+
+/// Only gathers light arriving at the first diffuse or glossy surface directly from emitters and
+/// the background, without any indirect bounces.
+///
+/// Mirrors and glass are still followed, up to `max_depth` bounces, so they show what they
+/// reflect and refract. Much faster and less noisy than a full path trace, at the cost of
+/// shadows being completely dark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirectLightingIntegrator {
+    /// Paths of specular bounces followed to the first diffuse or glossy surface.
+    path: PathIntegrator,
+}
+
+impl DirectLightingIntegrator {
+    /// Creates a new direct lighting integrator that follows at most `max_depth` specular bounces.
+    pub fn new(max_depth: u32) -> Self {
+        Self {
+            path: PathIntegrator::new(max_depth).russian_roulette_depth(u32::MAX),
+        }
+    }
+
+    /// Weighting of light sampling against material sampling when the camera has lights.
+    /// Defaults to [`MisHeuristic::Power`].
+    pub fn mis_heuristic(self, mis_heuristic: MisHeuristic) -> Self {
+        Self {
+            path: self.path.mis_heuristic(mis_heuristic),
+        }
+    }
+
+    /// Whether light is carried at sampled wavelengths rather than as RGB colours, as for
+    /// [`PathIntegrator::spectral`]. Defaults to false.
+    pub fn spectral(self, spectral: bool) -> Self {
+        Self {
+            path: self.path.spectral(spectral),
+        }
+    }
+}
+
+impl Integrator for DirectLightingIntegrator {
+    fn radiance(&self, r: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Colour {
+        self.path.trace(r, scene, sampler, true)
+    }
+
+    fn clone_box(&self) -> Box<dyn Integrator> {
+        Box::new(*self)
+    }
+}
@@ -0,0 +1,42 @@
+/// How direct lighting found by sampling the lights is combined with lighting found by sampling
+/// the material, when the camera has lights to sample.
+///
+/// Every light path can be found by either strategy, so each sample is weighted by how likely
+/// its strategy was to find it compared with the other. Light sampling then dominates for small
+/// lights and diffuse surfaces, and material sampling for large lights and glossy surfaces.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MisHeuristic {
+    /// Weights each strategy by its density over the sum of both densities.
+    Balance,
+    /// Weights each strategy by the square of its density over the sum of both squared
+    /// densities, which favours the better strategy more strongly and is usually less noisy.
+    #[default]
+    Power,
+}
+
+impl MisHeuristic {
+    /// The weight of a sample taken with density `pdf`, which the other strategy would have
+    /// taken with density `other_pdf`.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::integrator::MisHeuristic;
+    ///
+    /// assert_eq!(MisHeuristic::Balance.weight(3.0, 1.0), 0.75);
+    /// assert_eq!(MisHeuristic::Power.weight(3.0, 1.0), 0.9);
+    /// assert_eq!(MisHeuristic::Power.weight(2.0, 0.0), 1.0);
+    /// ```
+    pub fn weight(&self, pdf: f32, other_pdf: f32) -> f32 {
+        let (a, b) = match self {
+            MisHeuristic::Balance => (pdf, other_pdf),
+            MisHeuristic::Power => (pdf * pdf, other_pdf * other_pdf),
+        };
+        if a + b > 0.0 && a.is_finite() {
+            a / (a + b)
+        } else if a.is_finite() {
+            0.0
+        } else {
+            1.0
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use crate::{hittable::HitRecord, sampler::Sampler, Colour, Ray};
+
+use super::{Integrator, Scene};
+
+/// A debugging view that colours each pixel by the outward surface normal at the first hit,
+/// mapping each component from `[-1, 1]` to `[0, 1]`. Rays that miss are black.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NormalsIntegrator;
+
+impl Integrator for NormalsIntegrator {
+    fn radiance(&self, r: &Ray, scene: &Scene, _sampler: &mut dyn Sampler) -> Colour {
+        let mut rec = HitRecord::default();
+        if !scene.hit(r, &mut rec) {
+            return Colour::default();
+        }
+        let outward_normal = if rec.front_face {
+            rec.normal
+        } else {
+            -rec.normal
+        };
+        let n = 0.5 * (outward_normal.normalize() + 1.0);
+        Colour::new(n.x, n.y, n.z)
+    }
+
+    fn clone_box(&self) -> Box<dyn Integrator> {
+        Box::new(*self)
+    }
+}
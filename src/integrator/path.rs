@@ -0,0 +1,361 @@
+use crate::{
+    material::Scatter,
+    sampler::Sampler,
+    spectrum::{reflectance_colour, SampledSpectrum, SampledWavelengths, N_WAVELENGTHS},
+    Colour, Ray,
+};
+use std::ops::{AddAssign, Mul};
+
+use super::{Integrator, MisHeuristic, Scene};
+
+/// A unidirectional path tracer, which follows each camera ray as it bounces through the scene.
+///
+/// At each diffuse or glossy bounce the lights are sampled directly, and combined with the light
+/// found by the scattered ray using multiple importance sampling. Paths are ended by Russian
+/// roulette once they carry little light, or after `max_depth` bounces. This is the camera's
+/// default integrator.
+///
+/// Light is carried as RGB colours by default. In spectral mode each path instead carries light
+/// at a few wavelengths, so that light interacts with surfaces as it does in reality, rather
+/// than channel by channel: a yellow light, for example, is not made of red and green light, and
+/// lights up surfaces differently. Colours of materials and lights are converted to smooth
+/// spectra, and the light found is converted back to a colour at the end of each path.
+///
+/// # Example
+/// ```
+/// use ray_tracing::{camera::CameraBuilder, integrator::PathIntegrator};
+///
+/// let cam = CameraBuilder::default()
+///     .integrator(PathIntegrator::new(50).russian_roulette_depth(5))
+///     .build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathIntegrator {
+    /// Maximum number of ray bounces into scene.
+    max_depth: u32,
+    /// Bounces before Russian roulette may terminate a path.
+    russian_roulette_depth: u32,
+    /// Weighting of light sampling against material sampling.
+    mis_heuristic: MisHeuristic,
+    /// Whether paths carry light at sampled wavelengths rather than as RGB colours.
+    spectral: bool,
+}
+
+impl PathIntegrator {
+    /// Creates a new path tracer that follows paths for at most `max_depth` bounces.
+    pub fn new(max_depth: u32) -> Self {
+        Self {
+            max_depth,
+            russian_roulette_depth: 3,
+            mis_heuristic: MisHeuristic::default(),
+            spectral: false,
+        }
+    }
+
+    /// Bounces a path makes before Russian roulette may randomly terminate it, with a probability
+    /// based on how much light it can still carry to the camera. Terminated paths make the image
+    /// noisier but not darker, and save time on paths that contribute little. Defaults to 3; set
+    /// it to `max_depth` or more to disable Russian roulette.
+    pub fn russian_roulette_depth(self, russian_roulette_depth: u32) -> Self {
+        Self {
+            russian_roulette_depth,
+            ..self
+        }
+    }
+
+    /// Weighting of light sampling against material sampling when the camera has lights.
+    /// Defaults to [`MisHeuristic::Power`].
+    pub fn mis_heuristic(self, mis_heuristic: MisHeuristic) -> Self {
+        Self {
+            mis_heuristic,
+            ..self
+        }
+    }
+
+    /// Whether paths carry light at a few wavelengths sampled for each path, using hero
+    /// wavelength sampling, rather than as RGB colours. Spectral rendering gives the correct
+    /// colour for coloured light bouncing off coloured surfaces, and shows the dispersion of
+    /// materials such as [`Dielectric::diamond`](crate::material::Dielectric::diamond), at the
+    /// cost of some colour noise. Defaults to false.
+    pub fn spectral(self, spectral: bool) -> Self {
+        Self { spectral, ..self }
+    }
+
+    /// Follows a path from the camera ray `r`.
+    ///
+    /// With `direct_only`, the path ends at the first diffuse or glossy bounce, so only light
+    /// arriving there directly from emitters or the background is gathered.
+    pub(super) fn trace(
+        &self,
+        r: &Ray,
+        scene: &Scene,
+        sampler: &mut dyn Sampler,
+        direct_only: bool,
+    ) -> Colour {
+        if self.spectral {
+            let mut lambdas = SampledWavelengths::sample_visible(sampler.get_1d());
+            let radiance = self.trace_path(r, scene, sampler, direct_only, &mut lambdas);
+            // Dispersion may have changed which wavelengths carry light.
+            lambdas.to_colour(radiance)
+        } else {
+            self.trace_path(r, scene, sampler, direct_only, &mut RgbChannels)
+        }
+    }
+
+    /// Follows a path from the camera ray `r`, carrying light as described by `carrier`.
+    fn trace_path<C: LightCarrier>(
+        &self,
+        r: &Ray,
+        scene: &Scene,
+        sampler: &mut dyn Sampler,
+        direct_only: bool,
+        carrier: &mut C,
+    ) -> C::Light {
+        let mut path = PathState::new(carrier.tag(*r));
+        while path.bounces < self.max_depth {
+            let mut rec = Default::default();
+            if !scene.hit(&path.ray, &mut rec) {
+                path.add_light(carrier.light(scene.background(&path.ray)));
+                break;
+            }
+            let Some(mat) = rec.mat.clone() else {
+                break;
+            };
+            path.add_light(carrier.light(path.emission_weight * mat.emitted(&path.ray, &rec)));
+            if direct_only && path.scattering_bounces > 0 {
+                break;
+            }
+            path.bounces += 1;
+
+            let scatter = mat.scatter(&path.ray, &rec, sampler);
+            if scatter.is_some() && mat.is_dispersive(&rec) {
+                carrier.disperse();
+            }
+            match scatter {
+                Some(Scatter::Specular { attenuation, ray }) => {
+                    path.follow_specular(carrier.light(attenuation), carrier.tag(ray));
+                }
+                Some(Scatter::SpectralSpecular { reflectance, ray }) => {
+                    path.follow_specular(carrier.spectrum(reflectance.as_ref()), carrier.tag(ray));
+                }
+                Some(scatter) => {
+                    let (pdf, weight) = match scatter {
+                        Scatter::Pdf(pdf) => (pdf, 1.0),
+                        Scatter::WeightedPdf { pdf, weight } => (pdf, weight),
+                        Scatter::Specular { .. } | Scatter::SpectralSpecular { .. } => {
+                            unreachable!("specular scattering is handled above")
+                        }
+                    };
+                    // Both samples are always drawn so the following dimensions do not depend
+                    // on whether the light sample is used.
+                    let light_sample = sampler.get_2d();
+                    let scatter_sample = sampler.get_2d();
+                    if let Some((f, emitted)) = scene.sample_light(
+                        &path.ray,
+                        &rec,
+                        pdf.as_ref(),
+                        self.mis_heuristic,
+                        light_sample,
+                    ) {
+                        path.add_light(carrier.light(f * weight) * carrier.light(emitted));
+                    }
+
+                    // Importance sample the scattered direction, weighting it by the material's
+                    // response over the density it was chosen with.
+                    let scattered = carrier.tag(Ray::new(rec.p, pdf.generate(scatter_sample)));
+                    let pdf_value = pdf.value(*scattered.direction());
+                    if pdf_value <= 0.0 {
+                        break;
+                    }
+                    let f = mat.eval(&path.ray, &rec, &scattered) * (weight / pdf_value);
+                    path.emission_weight = match scene.lights {
+                        Some(lights) => {
+                            let light_pdf = lights.pdf_value(rec.p, *scattered.direction());
+                            self.mis_heuristic.weight(pdf_value, light_pdf)
+                        }
+                        None => 1.0,
+                    };
+                    path.throughput = path.throughput * carrier.light(f);
+                    path.ray = scattered;
+                    path.scattering_bounces += 1;
+                }
+                None => break,
+            }
+
+            let Some(survival) = self.russian_roulette(path.bounces, path.throughput, sampler)
+            else {
+                break;
+            };
+            path.throughput = path.throughput * (1.0 / survival);
+        }
+        path.radiance
+    }
+
+    /// Randomly terminates paths that can only carry a little light to the camera, once they
+    /// have made `bounces` bounces.
+    ///
+    /// The path survives with a probability equal to its `throughput`, and the light it gathers
+    /// afterwards is divided by that probability, so terminating it does not change the
+    /// expected image.
+    ///
+    /// # Returns
+    /// The probability the path survived with, or `None` if it was terminated.
+    fn russian_roulette(
+        &self,
+        bounces: u32,
+        throughput: impl PathLight,
+        sampler: &mut dyn Sampler,
+    ) -> Option<f32> {
+        if bounces < self.russian_roulette_depth {
+            return Some(1.0);
+        }
+        let survival = throughput.max_component().min(1.0);
+        (sampler.get_1d() < survival).then_some(survival)
+    }
+}
+
+impl Integrator for PathIntegrator {
+    fn radiance(&self, r: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Colour {
+        self.trace(r, scene, sampler, false)
+    }
+
+    fn clone_box(&self) -> Box<dyn Integrator> {
+        Box::new(*self)
+    }
+}
+
+/// Light carried along a path: RGB colours, or a spectrum sampled at the path's wavelengths.
+trait PathLight: Copy + Default + Mul<Output = Self> + Mul<f32, Output = Self> + AddAssign {
+    /// One in every channel or at every wavelength, so nothing is absorbed.
+    fn one() -> Self;
+
+    /// The largest value of any channel or wavelength.
+    fn max_component(&self) -> f32;
+}
+
+impl PathLight for Colour {
+    fn one() -> Self {
+        Colour::new(1.0, 1.0, 1.0)
+    }
+
+    fn max_component(&self) -> f32 {
+        Colour::max_component(self)
+    }
+}
+
+impl PathLight for SampledSpectrum {
+    fn one() -> Self {
+        SampledSpectrum::new([1.0; N_WAVELENGTHS])
+    }
+
+    fn max_component(&self) -> f32 {
+        SampledSpectrum::max_component(self)
+    }
+}
+
+/// How light is carried along a path: as RGB colours, or at a set of sampled wavelengths.
+trait LightCarrier {
+    /// The light carried.
+    type Light: PathLight;
+
+    /// Converts the colour of a material or light to the light carried.
+    fn light(&self, colour: Colour) -> Self::Light;
+
+    /// Converts a reflectance depending on the wavelength in nanometres to the light carried.
+    fn spectrum(&self, reflectance: &dyn Fn(f32) -> f32) -> Self::Light;
+
+    /// Tags a ray of the path with the wavelength it follows, if any.
+    fn tag(&self, ray: Ray) -> Ray;
+
+    /// Follows only the wavelength rays are tagged with, after a material has scattered each
+    /// wavelength in a different direction.
+    fn disperse(&mut self);
+}
+
+/// Carries light as RGB colours, which cannot be dispersed.
+struct RgbChannels;
+
+impl LightCarrier for RgbChannels {
+    type Light = Colour;
+
+    fn light(&self, colour: Colour) -> Colour {
+        colour
+    }
+
+    fn spectrum(&self, reflectance: &dyn Fn(f32) -> f32) -> Colour {
+        reflectance_colour(reflectance)
+    }
+
+    fn tag(&self, ray: Ray) -> Ray {
+        ray
+    }
+
+    fn disperse(&mut self) {}
+}
+
+impl LightCarrier for SampledWavelengths {
+    type Light = SampledSpectrum;
+
+    fn light(&self, colour: Colour) -> SampledSpectrum {
+        SampledSpectrum::from_colour(colour, self)
+    }
+
+    fn spectrum(&self, reflectance: &dyn Fn(f32) -> f32) -> SampledSpectrum {
+        SampledSpectrum::new(std::array::from_fn(|i| reflectance(self.lambda(i))))
+    }
+
+    fn tag(&self, ray: Ray) -> Ray {
+        ray.with_wavelength(self.lambda(0))
+    }
+
+    fn disperse(&mut self) {
+        self.terminate_secondary();
+    }
+}
+
+/// The state of a path as it bounces through the scene, carried from one bounce to the next by
+/// the path tracing loop.
+#[derive(Debug, Clone, Copy)]
+struct PathState<L> {
+    /// The ray leaving the path's last vertex.
+    ray: Ray,
+    /// Number of bounces made so far.
+    bounces: u32,
+    /// Number of those bounces that scattered light over a range of directions, rather than
+    /// along a single specular ray.
+    scattering_bounces: u32,
+    /// Fraction of the light arriving along `ray` that reaches the camera.
+    throughput: L,
+    /// Multiple importance sampling weight of light emitted by the surface `ray` hits, which is
+    /// less than one when that light could also have been found by sampling the lights.
+    emission_weight: f32,
+    /// Light gathered by the path so far.
+    radiance: L,
+}
+
+impl<L: PathLight> PathState<L> {
+    /// Starts a new path along the camera ray `ray`.
+    fn new(ray: Ray) -> Self {
+        Self {
+            ray,
+            bounces: 0,
+            scattering_bounces: 0,
+            throughput: L::one(),
+            emission_weight: 1.0,
+            radiance: L::default(),
+        }
+    }
+
+    /// Adds light arriving along the current ray to the path's radiance.
+    fn add_light(&mut self, light: L) {
+        self.radiance += self.throughput * light;
+    }
+
+    /// Continues the path along the specular ray `ray`, which passes on `attenuation` of the
+    /// light arriving along it.
+    fn follow_specular(&mut self, attenuation: L, ray: Ray) {
+        self.throughput = self.throughput * attenuation;
+        self.ray = ray;
+        self.emission_weight = 1.0;
+    }
+}
@@ -135,9 +135,9 @@ where
 ///
 /// This interval does not contain any values. It can be used to represent the concept
 /// of an "empty" or "nonexistent" interval.
-pub const EMPTY: Interval<f32> = Interval::new(std::f32::INFINITY, std::f32::NEG_INFINITY);
+pub const EMPTY: Interval<f32> = Interval::new(f32::INFINITY, f32::NEG_INFINITY);
 
 /// A constant representing the entire universe of `f32` values, from negative infinity to positive infinity.
 ///
 /// This interval includes all possible values of type `f32`.
-pub const UNIVERSE: Interval<f32> = Interval::new(std::f32::NEG_INFINITY, std::f32::INFINITY);
+pub const UNIVERSE: Interval<f32> = Interval::new(f32::NEG_INFINITY, f32::INFINITY);
@@ -1,20 +1,42 @@
-pub use glam::Vec3;
+pub use glam::{Vec2, Vec3};
 pub type Point3 = Vec3;
 
 mod colour;
+mod quad;
 /// Ray of light in 3D space.
 mod ray;
 mod sphere;
 
 pub use colour::Colour;
+pub use quad::Quad;
 pub use ray::Ray;
 pub use sphere::Sphere;
 
+/// Auxiliary per-pixel outputs for compositing and denoising.
+pub mod aov;
 pub mod camera;
+/// Noise removal for renders with few samples.
+pub mod denoise;
+/// Floating point sample accumulation.
+pub mod film;
+/// Pixel reconstruction filters.
+pub mod filter;
 pub mod hittable;
+/// In-memory images.
+pub mod image;
+/// Light transport algorithms.
+pub mod integrator;
 /// Interval utility.
 pub mod interval;
 pub mod material;
+/// Probability densities for importance sampling.
+pub mod pdf;
 pub mod random;
+/// Sample generation for pixels, lenses and bounces.
+pub mod sampler;
+/// Light sampled at wavelengths, for spectral rendering.
+pub mod spectrum;
+/// Surface properties varying over surfaces.
+pub mod texture;
 /// Various utility functions.
 pub mod util;
@@ -1,22 +1,97 @@
-use crate::{hittable::HitRecord, Colour, Ray};
+use crate::{hittable::HitRecord, pdf::Pdf, sampler::Sampler, Colour, Ray};
+
+/// How a material scatters an incoming ray.
+pub enum Scatter {
+    /// Light is scattered over a range of directions, which the integrator samples from `pdf`
+    /// and weights by [`Material::eval`] divided by the density.
+    Pdf(Box<dyn Pdf>),
+    /// Light is scattered over a range of directions as for [`Scatter::Pdf`], but `pdf` is only
+    /// one of the ways the material scatters, chosen at random. The light found through it, by
+    /// sampling either `pdf` or the lights, is multiplied by `weight`, the inverse of the
+    /// probability of the choice, to make up for the times another way was chosen.
+    WeightedPdf { pdf: Box<dyn Pdf>, weight: f32 },
+    /// Light is scattered along a single ray chosen by the material, such as a mirror reflection,
+    /// and multiplied by `attenuation`. The density of such a ray is a delta function, so it
+    /// cannot be combined with other sampling strategies.
+    Specular { attenuation: Colour, ray: Ray },
+    /// Light is scattered along a single ray as for [`Scatter::Specular`], but the fraction
+    /// passed on, `reflectance(λ)`, depends on the wavelength `λ` in nanometres. Each wavelength
+    /// carried by a spectral path is multiplied by its own reflectance, and RGB light by the
+    /// colour white light is reflected as.
+    SpectralSpecular {
+        reflectance: Box<dyn Fn(f32) -> f32>,
+        ray: Ray,
+    },
+}
 
 pub trait Material {
+    /// Scatters the incoming ray off the surface, drawing any random choices from `sampler`.
+    ///
+    /// # Returns
+    /// How the light is scattered, or `None` if the ray is absorbed.
+    #[allow(unused_variables)]
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord, sampler: &mut dyn Sampler) -> Option<Scatter> {
+        None
+    }
+
+    /// The fraction of light arriving along `scattered` that is scattered back along `r_in`,
+    /// including the cosine of the angle to the normal: the BRDF times the cosine term.
+    ///
+    /// Only materials returning [`Scatter::Pdf`] or [`Scatter::WeightedPdf`] need to implement
+    /// this.
+    #[allow(unused_variables)]
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Colour {
+        Colour::default()
+    }
+
+    /// The overall colour of the surface, ignoring how the reflected light is distributed over
+    /// directions. Not used for rendering the image, only as an auxiliary output for compositing
+    /// and as a denoising guide.
+    #[allow(unused_variables)]
+    fn albedo(&self, rec: &HitRecord) -> Colour {
+        Colour::default()
+    }
+
+    /// The light emitted by the surface back along `r_in`.
+    #[allow(unused_variables)]
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Colour {
+        Colour::default()
+    }
+
+    /// Whether the directions light is scattered in depend on its wavelength, as for glass
+    /// splitting white light into a rainbow. Spectral paths scattered by such a material follow
+    /// the direction of their hero wavelength, and stop carrying the other wavelengths.
     #[allow(unused_variables)]
-    fn scatter(
-        &self,
-        r_in: &Ray,
-        rec: &HitRecord,
-        attenuation: &mut Colour,
-        scattered: &mut Ray,
-    ) -> bool {
-        return false;
+    fn is_dispersive(&self, rec: &HitRecord) -> bool {
+        false
     }
 }
 
+mod blend;
+mod conductor;
 mod dielectric;
+mod diffuse_light;
+mod dispersion;
+mod fresnel;
 mod lambertian;
 mod metal;
+mod microfacet;
+mod oren_nayar;
+mod principled;
+mod rough_dielectric;
+/// Fixtures shared by the tests of materials and densities.
+#[cfg(test)]
+pub(crate) mod test_util;
+mod thin_film;
 
+pub use blend::Blend;
+pub use conductor::Conductor;
 pub use dielectric::Dielectric;
+pub use diffuse_light::DiffuseLight;
+pub use dispersion::Dispersion;
 pub use lambertian::Lambertian;
 pub use metal::Metal;
+pub use oren_nayar::OrenNayar;
+pub use principled::Principled;
+pub use rough_dielectric::RoughDielectric;
+pub use thin_film::ThinFilm;
@@ -0,0 +1,252 @@
+use crate::{
+    hittable::HitRecord, pdf::Pdf, sampler::Sampler, texture::Texture, Colour, Ray, Vec2, Vec3,
+};
+use std::rc::Rc;
+
+use super::{Material, Scatter};
+
+/// A stochastic blend of two materials, such as patches of rust on metal or dirt over paint.
+///
+/// Each scattered ray is scattered by one of the two materials, chosen at random in proportion
+/// to the blend weight, which can vary over the surface with a texture. The light scattered in
+/// each direction is the weighted sum of what both materials scatter, and the density of
+/// directions is the weighted sum of both materials' densities, so the blend works with light
+/// sampling.
+///
+/// # Example
+/// ```
+/// use ray_tracing::{
+///     material::{Blend, Lambertian, Metal},
+///     texture::Checker,
+///     Colour,
+/// };
+/// use std::rc::Rc;
+///
+/// let steel = Rc::new(Metal::new(&Colour::new(0.8, 0.8, 0.85), 0.1));
+/// let rust = Rc::new(Lambertian::new(&Colour::new(0.4, 0.15, 0.05)));
+/// let half_rusted = Blend::new(steel.clone(), rust.clone(), 0.5);
+/// let patchy = Blend::new(steel, rust, Checker::new(0.2, 0.1, 0.9));
+/// ```
+pub struct Blend {
+    /// The material where the weight is zero.
+    first: Rc<dyn Material>,
+    /// The material where the weight is one.
+    second: Rc<dyn Material>,
+    /// Fraction of the surface made of the second material, from 0 to 1.
+    weight: Rc<dyn Texture>,
+}
+
+impl Blend {
+    /// Creates a new blend of two materials.
+    ///
+    /// # Parameters
+    /// - `first`: The material where `weight` is zero.
+    /// - `second`: The material where `weight` is one.
+    /// - `weight`: How much of the second material there is, from 0 to 1, read from the red
+    ///   channel.
+    ///
+    /// # Returns
+    /// A new `Blend` material.
+    pub fn new(
+        first: Rc<dyn Material>,
+        second: Rc<dyn Material>,
+        weight: impl Texture + 'static,
+    ) -> Self {
+        Self {
+            first,
+            second,
+            weight: Rc::new(weight),
+        }
+    }
+
+    /// How much of the second material there is at the hit point `rec`.
+    fn weight(&self, rec: &HitRecord) -> f32 {
+        self.weight.value(rec).r().clamp(0.0, 1.0)
+    }
+
+    /// `first` and `second` mixed by the weight `w`.
+    fn mix(first: Colour, second: Colour, w: f32) -> Colour {
+        first * (1.0 - w) + second * w
+    }
+}
+
+impl Material for Blend {
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord, sampler: &mut dyn Sampler) -> Option<Scatter> {
+        let w = self.weight(rec);
+        let u = sampler.get_1d();
+        // Both materials always scatter, so the sample dimensions they use do not depend on
+        // which one is chosen.
+        let first = self.first.scatter(r_in, rec, sampler);
+        let second = self.second.scatter(r_in, rec, sampler);
+        let (chosen, other, other_probability) = if u < w {
+            (second, first, 1.0 - w)
+        } else {
+            (first, second, w)
+        };
+
+        let (pdf, weight) = match chosen? {
+            // A specular ray can only be found by the material that chose it, which is chosen in
+            // proportion to its share of the surface, so its attenuation is unchanged.
+            specular @ (Scatter::Specular { .. } | Scatter::SpectralSpecular { .. }) => {
+                return Some(specular)
+            }
+            Scatter::Pdf(pdf) => (pdf, 1.0),
+            Scatter::WeightedPdf { pdf, weight } => (pdf, weight),
+        };
+        // Each density is used with the probability of choosing its material, and of that
+        // material scattering with a density rather than specularly.
+        let mut pdfs = vec![((1.0 - other_probability) / weight, pdf)];
+        match other {
+            Some(Scatter::Pdf(pdf)) if other_probability > 0.0 => {
+                pdfs.push((other_probability, pdf))
+            }
+            Some(Scatter::WeightedPdf { pdf, weight }) if other_probability > 0.0 => {
+                pdfs.push((other_probability / weight, pdf))
+            }
+            _ => {}
+        }
+        let total: f32 = pdfs.iter().map(|(probability, _)| probability).sum();
+        let pdf = Box::new(BlendPdf { pdfs });
+        if total > 1.0 - 1e-6 {
+            Some(Scatter::Pdf(pdf))
+        } else {
+            // The densities are only used when a material scattering with one is chosen, so the
+            // light found through them is scaled up to make up for the other choices.
+            Some(Scatter::WeightedPdf {
+                pdf,
+                weight: 1.0 / total,
+            })
+        }
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Colour {
+        let w = self.weight(rec);
+        Self::mix(
+            self.first.eval(r_in, rec, scattered),
+            self.second.eval(r_in, rec, scattered),
+            w,
+        )
+    }
+
+    fn albedo(&self, rec: &HitRecord) -> Colour {
+        let w = self.weight(rec);
+        Self::mix(self.first.albedo(rec), self.second.albedo(rec), w)
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Colour {
+        let w = self.weight(rec);
+        Self::mix(
+            self.first.emitted(r_in, rec),
+            self.second.emitted(r_in, rec),
+            w,
+        )
+    }
+
+    fn is_dispersive(&self, rec: &HitRecord) -> bool {
+        self.first.is_dispersive(rec) || self.second.is_dispersive(rec)
+    }
+}
+
+/// The density of directions scattered by a [`Blend`], given that a material scattering over a
+/// range of directions was chosen.
+///
+/// It is the mixture of the densities of the materials, each weighted by how likely it is to be
+/// used. When the other material scatters specularly, only the chosen material's density is
+/// used, and [`Blend::scatter`] makes up for the specular rays with the weight of its
+/// [`Scatter::WeightedPdf`].
+struct BlendPdf {
+    /// The densities of the materials scattering over a range of directions, each with the
+    /// probability of it being used.
+    pdfs: Vec<(f32, Box<dyn Pdf>)>,
+}
+
+impl BlendPdf {
+    /// Sum of the probabilities of the densities.
+    fn total(&self) -> f32 {
+        self.pdfs.iter().map(|(probability, _)| probability).sum()
+    }
+}
+
+impl Pdf for BlendPdf {
+    fn value(&self, direction: Vec3) -> f32 {
+        let sum: f32 = self
+            .pdfs
+            .iter()
+            .map(|(probability, pdf)| probability * pdf.value(direction))
+            .sum();
+        sum / self.total()
+    }
+
+    fn generate(&self, u: Vec2) -> Vec3 {
+        // Choose a density in proportion to its probability with the first dimension, and
+        // stretch the part of it that chose the density back over [0, 1).
+        let total = self.total();
+        let mut start = 0.0;
+        for (k, (probability, pdf)) in self.pdfs.iter().enumerate() {
+            let share = probability / total;
+            if u.x < start + share || k == self.pdfs.len() - 1 {
+                let x = ((u.x - start) / share).clamp(0.0, 1.0 - f32::EPSILON);
+                return pdf.generate(Vec2::new(x, u.y));
+            }
+            start += share;
+        }
+        unreachable!("a blend density always has a density")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        material::{
+            test_util::{hit, scattered},
+            Lambertian, Metal,
+        },
+        sampler::StratifiedSampler,
+        Point3,
+    };
+
+    #[test]
+    fn scatters_the_weighted_sum_of_both_materials() {
+        let r_in = Ray::new(Point3::new(-1.0, 0.0, 1.0), Vec3::new(1.0, 0.0, -1.0));
+        let white = Rc::new(Lambertian::new(&Colour::new(1.0, 1.0, 1.0)));
+        let rough_metal = Rc::new(Metal::new(&Colour::new(0.5, 0.5, 0.5), 0.3));
+        let mirror = Rc::new(Metal::new(&Colour::new(0.5, 0.5, 0.5), 0.0));
+
+        for other in [rough_metal as Rc<dyn Material>, mirror] {
+            let expected = 0.25 * scattered(white.as_ref(), &r_in, &hit(true))
+                + 0.75 * scattered(other.as_ref(), &r_in, &hit(true));
+            let blend = Blend::new(white.clone(), other, 0.75);
+            let value = scattered(&blend, &r_in, &hit(true));
+            assert!((value - expected).abs() < 0.02, "{value} {expected}");
+        }
+    }
+
+    #[test]
+    fn density_covers_both_materials() {
+        let white = Rc::new(Lambertian::new(&Colour::new(1.0, 1.0, 1.0)));
+        let mirror = Rc::new(Metal::new(&Colour::new(0.5, 0.5, 0.5), 0.0));
+        let blend = Blend::new(white, mirror, 0.75);
+        let r_in = Ray::new(Point3::new(-1.0, 0.0, 1.0), Vec3::new(1.0, 0.0, -1.0));
+
+        // When the diffuse material is chosen, its density is used on its own, and the light
+        // found through it makes up for it only being chosen for a quarter of the samples.
+        let mut sampler = StratifiedSampler::new(8, 8);
+        let mut weighted = 0;
+        for k in 0..64 {
+            sampler.start_pixel_sample(0, 0, k);
+            match blend.scatter(&r_in, &hit(true), &mut sampler) {
+                Some(Scatter::WeightedPdf { pdf, weight }) => {
+                    let value = pdf.value(Vec3::Z);
+                    assert!((value - 1.0 / std::f32::consts::PI).abs() < 1e-6);
+                    assert!((weight - 4.0).abs() < 1e-5);
+                    weighted += 1;
+                }
+                Some(Scatter::Specular { .. }) => {}
+                _ => panic!("blend scattered neither like the mirror nor the diffuse material"),
+            }
+        }
+        assert_eq!(weighted, 16);
+        assert_eq!(blend.albedo(&hit(true)), Colour::new(0.625, 0.625, 0.625));
+    }
+}
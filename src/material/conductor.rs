@@ -0,0 +1,300 @@
+use crate::{
+    hittable::HitRecord,
+    sampler::Sampler,
+    spectrum::{reflectance_colour, SampledSpectrum},
+    util::{reflect, Onb},
+    Colour, Ray,
+};
+
+use super::{
+    fresnel,
+    microfacet::{MicrofacetReflectionPdf, TrowbridgeReitz},
+    Material, Scatter, ThinFilm,
+};
+
+/// A physically based metal, reflecting light off a rough surface made of tiny mirror facets.
+///
+/// The facet normals follow the GGX (Trowbridge-Reitz) distribution, with Smith masking and
+/// shadowing between facets, and reflected directions are sampled from the facets visible from
+/// the incoming ray. How much light each facet reflects is given by the Fresnel equations for the
+/// metal's complex refractive index `eta + i k`, which can be measured, so presets are provided
+/// for common metals. The facets can be coated with a [`ThinFilm`].
+///
+/// # Example
+/// ```
+/// use ray_tracing::{
+///     material::{Conductor, ThinFilm},
+///     Colour,
+/// };
+///
+/// let brushed_gold = Conductor::gold(0.3);
+/// let custom = Conductor::new(&Colour::new(1.5, 1.0, 0.8), &Colour::new(7.0, 6.0, 5.0), 0.1);
+/// // Copper tarnished by a thin layer of oxide.
+/// let tarnished = Conductor::copper(0.0).thin_film(ThinFilm::new(150.0, 2.6));
+/// ```
+pub struct Conductor {
+    /// Real part of the refractive index for each colour channel, relative to the surroundings.
+    eta: Colour,
+    /// Extinction coefficient, the imaginary part of the refractive index, for each channel.
+    k: Colour,
+    /// Distribution of facet normals.
+    distribution: TrowbridgeReitz,
+    /// Coating over the facets, if any.
+    thin_film: Option<ThinFilm>,
+}
+
+impl Conductor {
+    /// Creates a new conductor from its complex refractive index and roughness.
+    ///
+    /// # Parameters
+    /// - `eta`: The real part of the refractive index for the red, green and blue channels.
+    /// - `k`: The extinction coefficient for the red, green and blue channels.
+    /// - `roughness`: How rough the surface is, from 0 for a perfect mirror to 1 for a very
+    ///   rough surface.
+    ///
+    /// # Returns
+    /// A new `Conductor` material.
+    pub fn new(eta: &Colour, k: &Colour, roughness: f32) -> Self {
+        Self {
+            eta: *eta,
+            k: *k,
+            distribution: TrowbridgeReitz::from_roughness(roughness),
+            thin_film: None,
+        }
+    }
+
+    /// Gold, with the given roughness.
+    pub fn gold(roughness: f32) -> Self {
+        Self::new(
+            &Colour::new(0.143119, 0.374957, 1.44248),
+            &Colour::new(3.98316, 2.38572, 1.60322),
+            roughness,
+        )
+    }
+
+    /// Silver, with the given roughness.
+    pub fn silver(roughness: f32) -> Self {
+        Self::new(
+            &Colour::new(0.155265, 0.116723, 0.138342),
+            &Colour::new(4.82835, 3.12225, 2.14696),
+            roughness,
+        )
+    }
+
+    /// Copper, with the given roughness.
+    pub fn copper(roughness: f32) -> Self {
+        Self::new(
+            &Colour::new(0.200438, 0.924033, 1.10221),
+            &Colour::new(3.91295, 2.45285, 2.14219),
+            roughness,
+        )
+    }
+
+    /// Aluminium, with the given roughness.
+    pub fn aluminium(roughness: f32) -> Self {
+        Self::new(
+            &Colour::new(1.65746, 0.880369, 0.521229),
+            &Colour::new(9.22387, 6.26952, 4.837),
+            roughness,
+        )
+    }
+
+    /// Chromium, with the given roughness.
+    pub fn chromium(roughness: f32) -> Self {
+        Self::new(
+            &Colour::new(4.36968, 2.9167, 1.6547),
+            &Colour::new(5.20643, 4.23136, 3.75495),
+            roughness,
+        )
+    }
+
+    /// Coats the metal with the thin film `thin_film`, such as a layer of oxide, lying over every
+    /// facet. Defaults to no coating.
+    pub fn thin_film(self, thin_film: ThinFilm) -> Self {
+        Self {
+            thin_film: Some(thin_film),
+            ..self
+        }
+    }
+
+    /// Fraction of light reflected by a facet, for light of the given wavelength, or of each
+    /// colour if it has none, arriving at an angle with cosine `cos_theta` to the facet normal.
+    fn fresnel(&self, cos_theta: f32, wavelength: Option<f32>) -> Colour {
+        let Some(reflectance) = self.film_reflectance(cos_theta) else {
+            return fresnel::conductor_colour(cos_theta, self.eta, self.k);
+        };
+        match wavelength {
+            Some(lambda) => {
+                let reflectance = reflectance(lambda);
+                Colour::new(reflectance, reflectance, reflectance)
+            }
+            None => reflectance_colour(reflectance),
+        }
+    }
+
+    /// Fraction of the light at each wavelength, arriving at an angle with cosine `cos_theta` to
+    /// the facet normal, that the coating and the metal under it reflect, or `None` if the metal
+    /// is not coated.
+    fn film_reflectance(&self, cos_theta: f32) -> Option<impl Fn(f32) -> f32> {
+        let thin_film = self.thin_film?;
+        let (eta, k) = (self.eta, self.k);
+        Some(move |lambda| {
+            // The refractive index at `lambda`, from the smooth spectra of the RGB values.
+            let eta_lambda = SampledSpectrum::colour_at_wavelength(eta, lambda).max(0.01);
+            let k_lambda = SampledSpectrum::colour_at_wavelength(k, lambda).max(0.0);
+            thin_film.reflectance(cos_theta, lambda, eta_lambda, k_lambda)
+        })
+    }
+}
+
+impl Material for Conductor {
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord, _sampler: &mut dyn Sampler) -> Option<Scatter> {
+        let unit_direction = r_in.direction().normalize();
+        if self.distribution.is_smooth() {
+            let cos_theta = -unit_direction.dot(rec.normal);
+            let ray = Ray::new(rec.p, reflect(&unit_direction, &rec.normal));
+            return Some(match self.film_reflectance(cos_theta) {
+                // Every wavelength is reflected along the same ray, each by its own amount.
+                Some(reflectance) => Scatter::SpectralSpecular {
+                    reflectance: Box::new(reflectance),
+                    ray,
+                },
+                None => Scatter::Specular {
+                    attenuation: self.fresnel(cos_theta, None),
+                    ray,
+                },
+            });
+        }
+        let frame = Onb::new(rec.normal);
+        let wo = frame.to_local(-unit_direction);
+        Some(Scatter::Pdf(Box::new(MicrofacetReflectionPdf::new(
+            frame,
+            wo,
+            self.distribution,
+        ))))
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Colour {
+        let frame = Onb::new(rec.normal);
+        let wo = frame.to_local(-r_in.direction().normalize());
+        let wi = frame.to_local(scattered.direction().normalize());
+        if wo.z <= 0.0 || wi.z <= 0.0 {
+            return Colour::default();
+        }
+        let wm = (wo + wi).normalize();
+        // D F G / (4 cos θo cos θi), times the cosine term cos θi.
+        let d = self.distribution.d(wm);
+        let g = self.distribution.g(wo, wi);
+        self.fresnel(wo.dot(wm), r_in.wavelength()) * (d * g / (4.0 * wo.z))
+    }
+
+    fn albedo(&self, _rec: &HitRecord) -> Colour {
+        self.fresnel(1.0, None)
+    }
+
+    fn is_dispersive(&self, _rec: &HitRecord) -> bool {
+        // A rough coated metal is only evaluated at the hero wavelength, while a smooth one
+        // reflects every wavelength a path carries.
+        self.thin_film.is_some() && !self.distribution.is_smooth()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        material::test_util::{grid, hit, uniform_hemisphere},
+        sampler::IndependentSampler,
+        Point3, Vec2, Vec3,
+    };
+    use std::f32::consts::PI;
+
+    /// Fraction of the light arriving along `r_in` that the material reflects, estimated by
+    /// importance sampling the material.
+    fn reflectance(mat: &Conductor, r_in: &Ray) -> f32 {
+        let rec = hit(true);
+        let Some(Scatter::Pdf(pdf)) = mat.scatter(r_in, &rec, &mut IndependentSampler) else {
+            panic!("rough conductors scatter with a density");
+        };
+        let samples: Vec<Vec2> = grid(256).collect();
+        let sum: f32 = samples
+            .iter()
+            .map(|&u| {
+                let scattered = Ray::new(rec.p, pdf.generate(u));
+                let pdf_value = pdf.value(*scattered.direction());
+                if pdf_value <= 0.0 {
+                    return 0.0;
+                }
+                mat.eval(r_in, &rec, &scattered).luminance() / pdf_value
+            })
+            .sum();
+        sum / samples.len() as f32
+    }
+
+    #[test]
+    fn vanishing_films_reflect_like_the_metal() {
+        let copper = Conductor::copper(0.0);
+        let coated = Conductor::copper(0.0).thin_film(ThinFilm::new(0.0, 1.5));
+        let r_in = Ray::new(Point3::new(0.0, 0.0, 1.0), -Vec3::Z);
+        let rec = hit(true);
+        let Some(Scatter::Specular { attenuation, .. }) =
+            copper.scatter(&r_in, &rec, &mut IndependentSampler)
+        else {
+            panic!("smooth conductors scatter specularly");
+        };
+        let Some(Scatter::SpectralSpecular { reflectance, .. }) =
+            coated.scatter(&r_in, &rec, &mut IndependentSampler)
+        else {
+            panic!("smooth coated conductors scatter specularly");
+        };
+        let colour = reflectance_colour(reflectance);
+        let d = colour - attenuation;
+        assert!(
+            d.r().abs().max(d.g().abs()).max(d.b().abs()) < 0.05,
+            "{colour} {attenuation}"
+        );
+        // Spectral paths keep every wavelength off smooth coated metals, but not rough ones.
+        assert!(!coated.is_dispersive(&rec));
+        assert!(Conductor::copper(0.3)
+            .thin_film(ThinFilm::new(0.0, 1.5))
+            .is_dispersive(&rec));
+    }
+
+    #[test]
+    fn sampling_matches_reflectance() {
+        let mat = Conductor::copper(0.5);
+        let r_in = Ray::new(Point3::new(-1.0, 0.0, 1.0), Vec3::new(1.0, 0.0, -1.0));
+        // The same reflectance, estimated by integrating uniformly over the hemisphere.
+        let samples: Vec<Vec2> = grid(512).collect();
+        let uniform: f32 = samples
+            .iter()
+            .map(|&u| {
+                let scattered = Ray::new(Point3::ZERO, uniform_hemisphere(u));
+                mat.eval(&r_in, &hit(true), &scattered).luminance()
+            })
+            .sum::<f32>()
+            * 2.0
+            * PI
+            / samples.len() as f32;
+        assert!((reflectance(&mat, &r_in) / uniform - 1.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn perfect_conductor_conserves_energy() {
+        // With no real refractive index every facet reflects all light, so only light lost to
+        // masking and shadowing between facets is missing.
+        let white = Colour::new(1.0, 1.0, 1.0);
+        for roughness in [0.1, 0.5, 1.0] {
+            let mat = Conductor::new(&Colour::default(), &white, roughness);
+            for angle in [0.0f32, 45.0, 80.0] {
+                let angle = angle.to_radians();
+                let r_in = Ray::new(Point3::ZERO, Vec3::new(angle.sin(), 0.0, -angle.cos()));
+                let reflectance = reflectance(&mat, &r_in);
+                assert!(reflectance <= 1.001, "{roughness} {angle}: {reflectance}");
+                if roughness < 0.2 {
+                    assert!(reflectance > 0.98, "{roughness} {angle}: {reflectance}");
+                }
+            }
+        }
+    }
+}
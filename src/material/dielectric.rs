@@ -1,60 +1,395 @@
 use crate::{
     hittable::HitRecord,
-    random::random_f32,
+    sampler::Sampler,
     util::{reflect, refract},
     Colour, Ray,
 };
 
-use super::Material;
+use super::{Dispersion, Material, Scatter, ThinFilm};
 
+/// Wavelength of the helium d-line in nanometres, at which glass catalogues quote refractive
+/// indices.
+const D_LINE: f32 = 587.6;
+
+/// Smooth glass, reflecting or refracting each ray.
+///
+/// Clear by default. Coloured glass absorbs light as it travels through the object, following
+/// the Beer-Lambert law, so thick parts look darker and more saturated than thin ones.
+///
+/// # Example
+/// ```
+/// use ray_tracing::{material::Dielectric, Colour};
+///
+/// let clear = Dielectric::new(1.5);
+/// // Glass that lets 80% of red light through every unit of distance.
+/// let tinted = Dielectric::new(1.5).colour_at_distance(Colour::new(0.8, 0.9, 0.5), 1.0);
+/// ```
+///
+/// Glass can also bend each wavelength of light by a different amount, splitting white light
+/// into its colours. This dispersion only shows with a spectral integrator, such as
+/// [`PathIntegrator::spectral`](crate::integrator::PathIntegrator::spectral).
+///
+/// ```
+/// use ray_tracing::{
+///     material::{Dielectric, Dispersion},
+///     camera::CameraBuilder,
+///     integrator::PathIntegrator,
+/// };
+///
+/// let diamond = Dielectric::diamond();
+/// let flint = Dielectric::with_dispersion(Dispersion::Cauchy { a: 1.58, b: 0.0133 });
+/// let cam = CameraBuilder::default()
+///     .integrator(PathIntegrator::new(50).spectral(true))
+///     .build();
+/// ```
+///
+/// A [`ThinFilm`] coating on the outside of the glass gives it the colours of a soap bubble or
+/// the tinted reflections of a coated lens.
 pub struct Dielectric {
     /// Refractive index in vacuum or air, or the ratio of the material's refractive index over
     /// the refractive index of the enclosing media
     refraction_index: f32,
+    /// Fraction of each colour of light absorbed per unit of distance travelled inside.
+    absorption: Colour,
+    /// How the refractive index varies with wavelength, if it does.
+    dispersion: Option<Dispersion>,
+    /// Coating on the outside of the surface, if any.
+    thin_film: Option<ThinFilm>,
 }
 
 impl Dielectric {
     pub fn new(refraction_index: f32) -> Self {
-        Self { refraction_index }
+        Self {
+            refraction_index,
+            absorption: Colour::default(),
+            dispersion: None,
+            thin_film: None,
+        }
+    }
+
+    /// Creates glass whose refractive index varies with wavelength following `dispersion`.
+    /// Light carried as RGB colours uses the index at 587.6nm, the d-line quoted by glass
+    /// catalogues.
+    pub fn with_dispersion(dispersion: Dispersion) -> Self {
+        Self {
+            dispersion: Some(dispersion),
+            ..Self::new(dispersion.refractive_index(D_LINE))
+        }
+    }
+
+    /// Dispersive Schott N-BK7 optical glass, see [`Dispersion::BK7`].
+    pub fn bk7() -> Self {
+        Self::with_dispersion(Dispersion::BK7)
+    }
+
+    /// Dispersive fused silica, see [`Dispersion::FUSED_SILICA`].
+    pub fn fused_silica() -> Self {
+        Self::with_dispersion(Dispersion::FUSED_SILICA)
+    }
+
+    /// Dispersive diamond, see [`Dispersion::DIAMOND`].
+    pub fn diamond() -> Self {
+        Self::with_dispersion(Dispersion::DIAMOND)
+    }
+
+    /// The refractive index for the light carried by `r_in`.
+    fn refraction_index(&self, r_in: &Ray) -> f32 {
+        self.refraction_index_at(r_in.wavelength())
+    }
+
+    /// The refractive index at the wavelength `lambda` in nanometres, or the index quoted by
+    /// catalogues without a wavelength.
+    fn refraction_index_at(&self, lambda: Option<f32>) -> f32 {
+        match (self.dispersion, lambda) {
+            (Some(dispersion), Some(lambda)) => dispersion.refractive_index(lambda),
+            _ => self.refraction_index,
+        }
+    }
+
+    /// Coats the outside of the surface with the thin film `thin_film`, which reflects light
+    /// arriving from outside in place of the bare surface. Defaults to no coating.
+    pub fn thin_film(self, thin_film: ThinFilm) -> Self {
+        Self {
+            thin_film: Some(thin_film),
+            ..self
+        }
+    }
+
+    /// Absorption coefficient of each colour channel, the rate at which light is absorbed per
+    /// unit of distance travelled inside the object. Defaults to black, for clear glass.
+    pub fn absorption(self, absorption: Colour) -> Self {
+        Self { absorption, ..self }
+    }
+
+    /// Sets the absorption so that light travelling `distance` inside the object is tinted
+    /// `colour`. A channel can't absorb everything in a finite distance, so channels of `colour`
+    /// are clamped to at least [`MIN_TRANSMITTED`](Self::MIN_TRANSMITTED), and to at most one.
+    ///
+    /// # Panics
+    /// Panics if `distance` isn't above zero.
+    pub fn colour_at_distance(self, colour: Colour, distance: f32) -> Self {
+        assert!(distance > 0.0);
+        let coefficient =
+            |transmitted: f32| -transmitted.clamp(Self::MIN_TRANSMITTED, 1.0).ln() / distance;
+        self.absorption(Colour::new(
+            coefficient(colour.r()),
+            coefficient(colour.g()),
+            coefficient(colour.b()),
+        ))
+    }
+
+    /// The smallest fraction of a colour [`colour_at_distance`](Self::colour_at_distance) lets
+    /// through.
+    pub const MIN_TRANSMITTED: f32 = 1e-4;
+
+    /// Fraction of light surviving the path along `r_in` to `rec`. Light is only absorbed when
+    /// it hits the surface from inside the object, having travelled through it.
+    fn transmittance(&self, r_in: &Ray, rec: &HitRecord) -> Colour {
+        if rec.front_face {
+            return Colour::new(1.0, 1.0, 1.0);
+        }
+        let distance = rec.t * r_in.direction().length();
+        Colour::new(
+            (-self.absorption.r() * distance).exp(),
+            (-self.absorption.g() * distance).exp(),
+            (-self.absorption.b() * distance).exp(),
+        )
     }
 
     fn reflectance(cosine: f32, refraction_index: f32) -> f32 {
         // Use Schlick's approximation for reflectance.
         let mut r0 = (1.0 - refraction_index) / (1.0 + refraction_index);
         r0 = r0 * r0;
-        return r0 + (1.0 - r0) * (1.0 - cosine).powi(5);
+        r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
+    }
+
+    /// Reflects or refracts light arriving along `r_in` from outside through the coating
+    /// `thin_film`, with `u` choosing between them.
+    fn scatter_coated(&self, thin_film: &ThinFilm, r_in: &Ray, rec: &HitRecord, u: f32) -> Scatter {
+        let unit_direction = r_in.direction().normalize();
+        let cos_theta = f32::min(-unit_direction.dot(rec.normal), 1.0);
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        let cannot_refract = |refraction_index: f32| sin_theta / refraction_index > 1.0;
+        let reflected = Ray::new(rec.p, reflect(&unit_direction, &rec.normal));
+        let refracted = |refraction_index: f32| {
+            let direction = refract(&unit_direction, &rec.normal, 1.0 / refraction_index);
+            Ray::new(rec.p, direction)
+        };
+        let white = Colour::new(1.0, 1.0, 1.0);
+
+        if let Some(lambda) = r_in.wavelength() {
+            // A single wavelength is either reflected or refracted, so the choice alone accounts
+            // for the reflectance.
+            let refraction_index = self.refraction_index(r_in);
+            let ray = if cannot_refract(refraction_index)
+                || thin_film.reflectance(cos_theta, lambda, refraction_index, 0.0) > u
+            {
+                reflected
+            } else {
+                refracted(refraction_index)
+            };
+            return Scatter::Specular {
+                attenuation: white,
+                ray,
+            };
+        }
+
+        if cannot_refract(self.refraction_index) {
+            return Scatter::Specular {
+                attenuation: white,
+                ray: reflected,
+            };
+        }
+
+        // Each colour is reflected by a different amount, so reflect with the average
+        // reflectance and tint each choice by how much of each colour takes it.
+        let reflectance = thin_film.reflectance_colour(cos_theta, |lambda| {
+            (self.refraction_index_at(Some(lambda)), 0.0)
+        });
+        let p =
+            ((reflectance.r() + reflectance.g() + reflectance.b()) / 3.0).clamp(1e-3, 1.0 - 1e-3);
+        if u < p {
+            Scatter::Specular {
+                attenuation: reflectance * (1.0 / p),
+                ray: reflected,
+            }
+        } else {
+            Scatter::Specular {
+                attenuation: (white - reflectance) * (1.0 / (1.0 - p)),
+                ray: refracted(self.refraction_index),
+            }
+        }
     }
 }
 
 impl Material for Dielectric {
-    fn scatter(
-        &self,
-        r_in: &Ray,
-        rec: &HitRecord,
-        attenuation: &mut Colour,
-        scattered: &mut Ray,
-    ) -> bool {
-        *attenuation = Colour::new(1.0, 1.0, 1.0);
-
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord, sampler: &mut dyn Sampler) -> Option<Scatter> {
+        if let (Some(thin_film), true) = (&self.thin_film, rec.front_face) {
+            let u = sampler.get_1d();
+            return Some(self.scatter_coated(thin_film, r_in, rec, u));
+        }
+        let refraction_index = self.refraction_index(r_in);
         let ri = if rec.front_face {
-            1.0 / self.refraction_index
+            1.0 / refraction_index
         } else {
-            self.refraction_index
+            refraction_index
         };
 
         let unit_direction = r_in.direction().normalize();
 
-        let cos_theta = f32::min(-1.0 * unit_direction.dot(rec.normal), 1.0);
+        let cos_theta = f32::min(-unit_direction.dot(rec.normal), 1.0);
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
 
         let cannot_refract = ri * sin_theta > 1.0;
 
-        let direction = if cannot_refract || Self::reflectance(cos_theta, ri) > random_f32() {
+        let u = sampler.get_1d();
+        let direction = if cannot_refract || Self::reflectance(cos_theta, ri) > u {
             reflect(&unit_direction, &rec.normal)
         } else {
             refract(&unit_direction, &rec.normal, ri)
         };
-        *scattered = Ray::new(rec.p, direction);
-        true
+        Some(Scatter::Specular {
+            attenuation: self.transmittance(r_in, rec),
+            ray: Ray::new(rec.p, direction),
+        })
+    }
+
+    fn albedo(&self, _rec: &HitRecord) -> Colour {
+        // The surface absorbs nothing, so the colour is that of the light after travelling a
+        // unit of distance inside, which is white for clear glass.
+        Colour::new(
+            (-self.absorption.r()).exp(),
+            (-self.absorption.g()).exp(),
+            (-self.absorption.b()).exp(),
+        )
+    }
+
+    fn is_dispersive(&self, rec: &HitRecord) -> bool {
+        // Spectral paths follow the reflection of their hero wavelength off a coating, which
+        // only covers the outside of the object.
+        self.dispersion.is_some() || (self.thin_film.is_some() && rec.front_face)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::test_util::hit, sampler::IndependentSampler, Point3, Vec3};
+
+    /// The attenuation of a ray along `direction` hitting the `z = 0` plane at distance `t`.
+    fn attenuation(mat: &Dielectric, direction: Vec3, t: f32, front_face: bool) -> Colour {
+        let rec = HitRecord {
+            t,
+            ..hit(front_face)
+        };
+        let r_in = Ray::new(Point3::ZERO - t * direction, direction);
+        match mat.scatter(&r_in, &rec, &mut IndependentSampler) {
+            Some(Scatter::Specular { attenuation, .. }) => attenuation,
+            _ => panic!("glass always scatters specularly"),
+        }
+    }
+
+    #[test]
+    fn absorbs_light_travelling_inside() {
+        let colour = Colour::new(0.8, 0.5, 0.2);
+        let glass = Dielectric::new(1.5).colour_at_distance(colour, 2.0);
+        let close = |a: Colour, b: Colour| {
+            let d = a - b;
+            [d.r(), d.g(), d.b()].iter().all(|x| x.abs() < 1e-5)
+        };
+
+        // Entering the glass absorbs nothing.
+        let entering = attenuation(&glass, -Vec3::Z, 2.0, true);
+        assert_eq!(entering, Colour::new(1.0, 1.0, 1.0));
+        // Leaving it absorbs according to the distance travelled, whatever the ray's speed.
+        assert!(close(attenuation(&glass, Vec3::Z, 2.0, false), colour));
+        assert!(close(
+            attenuation(&glass, 2.0 * Vec3::Z, 1.0, false),
+            colour
+        ));
+        let twice = colour * colour;
+        assert!(close(attenuation(&glass, Vec3::Z, 4.0, false), twice));
+        // Clear glass absorbs nothing.
+        let clear = attenuation(&Dielectric::new(1.5), Vec3::Z, 4.0, false);
+        assert_eq!(clear, Colour::new(1.0, 1.0, 1.0));
+        // The albedo is the colour after a unit of distance, half the one it was given for.
+        let unit = Colour::new(0.8_f32.sqrt(), 0.5_f32.sqrt(), 0.2_f32.sqrt());
+        assert!(close(glass.albedo(&hit(true)), unit));
+        let clear_albedo = Dielectric::new(1.5).albedo(&hit(true));
+        assert_eq!(clear_albedo, Colour::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn opaque_channels_absorb_a_finite_amount() {
+        let glass = Dielectric::new(1.5).colour_at_distance(Colour::new(0.0, 0.5, 1.0), 2.0);
+        for t in [0.0, 2.0, 100.0] {
+            let colour = attenuation(&glass, Vec3::Z, t, false);
+            assert!([colour.r(), colour.g(), colour.b()]
+                .iter()
+                .all(|c| c.is_finite() && (0.0..=1.0).contains(c)));
+        }
+        let colour = attenuation(&glass, Vec3::Z, 2.0, false);
+        assert!((colour.r() - Dielectric::MIN_TRANSMITTED).abs() < 1e-6);
+        assert_eq!(
+            attenuation(&glass, Vec3::Z, 0.0, false),
+            Colour::new(1.0, 1.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn coated_surfaces_reflect_everything_past_the_critical_angle() {
+        // A bubble of air in water is less dense than its surroundings, so light arriving at a
+        // grazing angle cannot get in.
+        let bubble = Dielectric::new(0.75).thin_film(ThinFilm::new(400.0, 1.33));
+        let rec = hit(true);
+        let direction = Vec3::new(1.0, 0.0, -0.2);
+        for r_in in [
+            Ray::new(Point3::ZERO - direction, direction),
+            Ray::new(Point3::ZERO - direction, direction).with_wavelength(550.0),
+        ] {
+            for _ in 0..32 {
+                let Some(Scatter::Specular { attenuation, ray }) =
+                    bubble.scatter(&r_in, &rec, &mut IndependentSampler)
+                else {
+                    panic!("glass always scatters specularly");
+                };
+                assert!(ray.direction().z > 0.0);
+                assert_eq!(attenuation, Colour::new(1.0, 1.0, 1.0));
+            }
+        }
+    }
+
+    #[test]
+    fn coatings_only_disperse_light_hitting_the_outside() {
+        let coated = Dielectric::new(1.5).thin_film(ThinFilm::new(300.0, 1.38));
+        assert!(coated.is_dispersive(&hit(true)));
+        assert!(!coated.is_dispersive(&hit(false)));
+    }
+
+    #[test]
+    fn blue_light_bends_more_than_red() {
+        let diamond = Dielectric::diamond();
+        let rec = HitRecord {
+            t: 1.0,
+            ..hit(true)
+        };
+        // The direction a ray of the given wavelength is refracted in.
+        let refracted = |lambda: f32| loop {
+            let r_in = Ray::new(Point3::new(-1.0, 0.0, 1.0), Vec3::new(1.0, 0.0, -1.0));
+            let r_in = r_in.with_wavelength(lambda);
+            if let Some(Scatter::Specular { ray, .. }) =
+                diamond.scatter(&r_in, &rec, &mut IndependentSampler)
+            {
+                if ray.direction().z < 0.0 {
+                    return ray.direction().normalize();
+                }
+            }
+        };
+        let (blue, red) = (refracted(450.0), refracted(650.0));
+        // Blue is bent further towards the normal, so travels less sideways.
+        assert!(blue.x < red.x - 0.002);
+        // Without a wavelength the index quoted by catalogues is used.
+        assert!(diamond.is_dispersive(&rec));
+        assert!((diamond.refraction_index - 2.4175).abs() < 1e-3);
     }
 }
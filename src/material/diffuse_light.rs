@@ -0,0 +1,25 @@
+use crate::{hittable::HitRecord, Colour, Ray};
+
+use super::Material;
+
+/// A material that emits light evenly from its front face and does not scatter any.
+pub struct DiffuseLight {
+    /// Emitted radiance.
+    emit: Colour,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: &Colour) -> Self {
+        Self { emit: *emit }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn emitted(&self, _r_in: &Ray, rec: &HitRecord) -> Colour {
+        if rec.front_face {
+            self.emit
+        } else {
+            Colour::default()
+        }
+    }
+}
@@ -0,0 +1,101 @@
+/// How the refractive index of a transparent material varies with the wavelength of light,
+/// which splits white light into its colours, as in a prism's rainbow or a diamond's fire.
+///
+/// Wavelengths are given in nanometres, while the coefficients follow the usual convention of
+/// optical glass catalogues, with wavelengths in micrometres.
+///
+/// # Example
+/// ```
+/// use ray_tracing::material::Dispersion;
+///
+/// let bk7 = Dispersion::BK7;
+/// assert!((bk7.refractive_index(587.6) - 1.5168).abs() < 1e-4);
+/// // Blue light is bent more than red light.
+/// assert!(bk7.refractive_index(450.0) > bk7.refractive_index(650.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dispersion {
+    /// Cauchy's equation, `n = a + b / λ²`, a simple fit for glasses over visible light.
+    Cauchy {
+        /// Refractive index approached at long wavelengths.
+        a: f32,
+        /// Strength of the dispersion, in square micrometres.
+        b: f32,
+    },
+    /// The Sellmeier equation, `n² = 1 + Σ bᵢ λ² / (λ² - cᵢ)`, accurate over a wide range of
+    /// wavelengths, for which glass manufacturers publish coefficients.
+    Sellmeier {
+        /// Strength of each absorption resonance.
+        b: [f32; 3],
+        /// Square of the wavelength of each resonance, in square micrometres.
+        c: [f32; 3],
+    },
+}
+
+impl Dispersion {
+    /// Schott N-BK7, the most common optical glass.
+    pub const BK7: Self = Self::Sellmeier {
+        b: [1.039_612, 0.231_792_34, 1.010_469_5],
+        c: [0.006_000_699, 0.020_017_914, 103.560_65],
+    };
+
+    /// Fused silica, pure glassy quartz, from Malitson's measurements.
+    pub const FUSED_SILICA: Self = Self::Sellmeier {
+        b: [0.696_166_3, 0.407_942_6, 0.897_479_4],
+        c: [0.004_679_148, 0.013_512_063, 97.934],
+    };
+
+    /// Diamond, whose high and strongly varying refractive index gives its fire.
+    pub const DIAMOND: Self = Self::Sellmeier {
+        b: [0.3306, 4.3356, 0.0],
+        c: [0.030_625, 0.011_236, 0.0],
+    };
+
+    /// The refractive index for light of wavelength `lambda`, in nanometres.
+    pub fn refractive_index(&self, lambda: f32) -> f32 {
+        let lambda2 = (lambda / 1000.0).powi(2);
+        match self {
+            Self::Cauchy { a, b } => a + b / lambda2,
+            Self::Sellmeier { b, c } => {
+                let n2 = 1.0
+                    + b.iter()
+                        .zip(c)
+                        .map(|(b, c)| b * lambda2 / (lambda2 - c))
+                        .sum::<f32>();
+                n2.sqrt()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_match_catalogue_indices() {
+        // Refractive indices at the helium d-line and sodium D-line.
+        assert!((Dispersion::BK7.refractive_index(587.6) - 1.5168).abs() < 1e-4);
+        assert!((Dispersion::FUSED_SILICA.refractive_index(587.6) - 1.4585).abs() < 1e-4);
+        assert!((Dispersion::DIAMOND.refractive_index(589.3) - 2.417).abs() < 1e-3);
+    }
+
+    #[test]
+    fn shorter_wavelengths_refract_more() {
+        let cauchy = Dispersion::Cauchy { a: 1.5, b: 0.004 };
+        for dispersion in [
+            cauchy,
+            Dispersion::BK7,
+            Dispersion::FUSED_SILICA,
+            Dispersion::DIAMOND,
+        ] {
+            let mut previous = f32::INFINITY;
+            for lambda in (400..=700).step_by(50) {
+                let n = dispersion.refractive_index(lambda as f32);
+                assert!(n < previous && n > 1.0);
+                previous = n;
+            }
+        }
+        assert!((cauchy.refractive_index(500.0) - 1.516).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::Colour;
+
+/// Fraction of unpolarised light reflected by a conductor with complex refractive index
+/// `eta + i k`, relative to the surrounding medium, for light arriving at an angle with cosine
+/// `cos_theta` to the normal.
+pub(super) fn conductor(cos_theta: f32, eta: f32, k: f32) -> f32 {
+    let cos2 = cos_theta.clamp(0.0, 1.0).powi(2);
+    let sin2 = 1.0 - cos2;
+    let eta2 = eta * eta;
+    let k2 = k * k;
+
+    let t0 = eta2 - k2 - sin2;
+    let a2_plus_b2 = (t0 * t0 + 4.0 * eta2 * k2).sqrt();
+    let a = (0.5 * (a2_plus_b2 + t0)).max(0.0).sqrt();
+    let t1 = a2_plus_b2 + cos2;
+    let t2 = 2.0 * cos_theta * a;
+    let rs = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2 * a2_plus_b2 + sin2 * sin2;
+    let t4 = t2 * sin2;
+    let rp = rs * (t3 - t4) / (t3 + t4);
+    0.5 * (rs + rp)
+}
+
+/// The complex refractive index `eta + i k` of a conductor reflecting `reflectance` of the light
+/// arriving head on, with reflections tinted towards `edge_tint` away from grazing angles,
+/// following Gulbrandsen's artist friendly metallic Fresnel.
+///
+/// # Returns
+/// The pair `(eta, k)`.
+pub(super) fn conductor_index(reflectance: f32, edge_tint: f32) -> (f32, f32) {
+    let r = reflectance.clamp(0.0, 0.99);
+    let g = edge_tint.clamp(0.0, 1.0);
+    let sqrt_r = r.sqrt();
+    let eta = g * (1.0 - r) / (1.0 + r) + (1.0 - g) * (1.0 + sqrt_r) / (1.0 - sqrt_r);
+    let k2 = (r * (eta + 1.0).powi(2) - (eta - 1.0).powi(2)) / (1.0 - r);
+    (eta, k2.max(0.0).sqrt())
+}
+
+/// Fraction of unpolarised light reflected at a smooth interface between dielectrics, for light
+/// arriving at an angle with cosine `cos_theta` to the normal. `eta` is the refractive index on
+/// the far side of the interface over the index on the near side; light arriving from the far
+/// side has a negative cosine.
+pub(super) fn dielectric(cos_theta: f32, eta: f32) -> f32 {
+    let (cos_i, eta) = if cos_theta < 0.0 {
+        (-cos_theta.max(-1.0), 1.0 / eta)
+    } else {
+        (cos_theta.min(1.0), eta)
+    };
+    let sin2_t = (1.0 - cos_i * cos_i) / (eta * eta);
+    if sin2_t >= 1.0 {
+        // Total internal reflection.
+        return 1.0;
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+    let r_parallel = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    let r_perpendicular = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    0.5 * (r_parallel * r_parallel + r_perpendicular * r_perpendicular)
+}
+
+/// [`conductor`] for each colour channel.
+pub(super) fn conductor_colour(cos_theta: f32, eta: Colour, k: Colour) -> Colour {
+    Colour::new(
+        conductor(cos_theta, eta.r(), k.r()),
+        conductor(cos_theta, eta.g(), k.g()),
+        conductor(cos_theta, eta.b(), k.b()),
+    )
+}
+
+/// Schlick's approximation of the fraction of light reflected by a surface whose reflectance
+/// head on is `f0`, for light arriving at an angle with cosine `cos_theta` to the normal.
+pub(super) fn schlick(cos_theta: f32, f0: Colour) -> Colour {
+    let weight = (1.0 - cos_theta.clamp(0.0, 1.0)).powi(5);
+    f0 + (Colour::new(1.0, 1.0, 1.0) - f0) * weight
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conductor_reflectance() {
+        // At normal incidence the reflectance has a simple closed form.
+        let (eta, k) = (0.2, 3.9);
+        let normal = ((eta - 1.0f32).powi(2) + k * k) / ((eta + 1.0f32).powi(2) + k * k);
+        assert!((conductor(1.0, eta, k) - normal).abs() < 1e-5);
+        // Every conductor becomes a perfect mirror at grazing angles.
+        assert!((conductor(0.0, eta, k) - 1.0).abs() < 1e-5);
+        // Without absorption it matches a dielectric, which transmits most light head on.
+        assert!((conductor(1.0, 1.5, 0.0) - 0.04).abs() < 1e-5);
+    }
+
+    #[test]
+    fn conductor_index_reflects_the_reflectance_head_on() {
+        for (reflectance, edge_tint) in [(0.9, 0.9), (0.5, 0.8), (0.3, 0.3), (0.05, 0.5)] {
+            let (eta, k) = conductor_index(reflectance, edge_tint);
+            let value = conductor(1.0, eta, k);
+            assert!((value - reflectance).abs() < 1e-4, "{value} {reflectance}");
+        }
+    }
+
+    #[test]
+    fn dielectric_reflectance() {
+        assert!((dielectric(1.0, 1.5) - 0.04).abs() < 1e-5);
+        // The same interface seen from either side reflects the same head on.
+        assert!((dielectric(-1.0, 1.5) - 0.04).abs() < 1e-5);
+        assert!((dielectric(0.0, 1.5) - 1.0).abs() < 1e-5);
+        // Beyond the critical angle, light inside the denser medium is totally reflected.
+        let critical = (1.0f32 / 1.5).asin().cos();
+        assert_eq!(dielectric(-(critical - 0.01), 1.5), 1.0);
+        assert!(dielectric(-(critical + 0.01), 1.5) < 1.0);
+        // The interface looks the same as a conductor without absorption.
+        for cos_theta in [0.2, 0.5, 0.9] {
+            assert!((dielectric(cos_theta, 1.5) - conductor(cos_theta, 1.5, 0.0)).abs() < 1e-5);
+        }
+    }
+}
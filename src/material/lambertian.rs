@@ -1,8 +1,7 @@
-use crate::{
-    hittable::HitRecord, random::random_unit_vector, util::is_vec3_near_zero, Colour, Ray,
-};
+use crate::{hittable::HitRecord, pdf::CosinePdf, sampler::Sampler, Colour, Ray};
+use std::f32::consts::PI;
 
-use super::Material;
+use super::{Material, Scatter};
 
 pub struct Lambertian {
     albedo: Colour,
@@ -15,22 +14,16 @@ impl Lambertian {
 }
 
 impl Material for Lambertian {
-    fn scatter(
-        &self,
-        _r_in: &Ray,
-        rec: &HitRecord,
-        attenuation: &mut Colour,
-        scattered: &mut Ray,
-    ) -> bool {
-        let mut scatter_direction = rec.normal + random_unit_vector();
+    fn scatter(&self, _r_in: &Ray, rec: &HitRecord, _sampler: &mut dyn Sampler) -> Option<Scatter> {
+        Some(Scatter::Pdf(Box::new(CosinePdf::new(rec.normal))))
+    }
 
-        // Catch degenerate scatter direction
-        if is_vec3_near_zero(scatter_direction) {
-            scatter_direction = rec.normal;
-        }
+    fn eval(&self, _r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Colour {
+        let cosine = rec.normal.dot(scattered.direction().normalize());
+        self.albedo * (cosine.max(0.0) / PI)
+    }
 
-        *scattered = Ray::new(rec.p, scatter_direction);
-        *attenuation = self.albedo;
-        return true;
+    fn albedo(&self, _rec: &HitRecord) -> Colour {
+        self.albedo
     }
 }
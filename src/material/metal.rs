@@ -1,10 +1,19 @@
-use crate::{hittable::HitRecord, random::random_unit_vector, util::reflect, Colour, Ray};
+use crate::{
+    hittable::HitRecord,
+    pdf::Pdf,
+    sampler::{sample_unit_vector, Sampler},
+    spectrum::{reflectance_colour, SampledSpectrum},
+    util::reflect,
+    Colour, Ray, Vec2, Vec3,
+};
+use std::f32::consts::PI;
 
-use super::Material;
+use super::{fresnel, Material, Scatter, ThinFilm};
 
 pub struct Metal {
     albedo: Colour,
     fuzz: f32,
+    thin_film: Option<ThinFilm>,
 }
 
 impl Metal {
@@ -12,22 +21,195 @@ impl Metal {
         Self {
             albedo: *albedo,
             fuzz: if fuzz > 1.0 { 1.0 } else { fuzz },
+            thin_film: None,
         }
     }
+
+    /// Coats the metal with the thin film `thin_film`. The metal under the film has the complex
+    /// refractive index that reflects its albedo head on. Defaults to no coating.
+    pub fn thin_film(self, thin_film: ThinFilm) -> Self {
+        Self {
+            thin_film: Some(thin_film),
+            ..self
+        }
+    }
+
+    /// Fraction of each colour of the light arriving along `r_in` that is reflected.
+    fn reflectance(&self, r_in: &Ray, rec: &HitRecord) -> Colour {
+        let Some(reflectance) = self.film_reflectance(r_in, rec) else {
+            return self.albedo;
+        };
+        match r_in.wavelength() {
+            Some(lambda) => {
+                let reflectance = reflectance(lambda);
+                Colour::new(reflectance, reflectance, reflectance)
+            }
+            None => reflectance_colour(reflectance),
+        }
+    }
+
+    /// Fraction of the light arriving along `r_in` at each wavelength that the coating and the
+    /// metal under it reflect, or `None` if the metal is not coated.
+    fn film_reflectance(&self, r_in: &Ray, rec: &HitRecord) -> Option<impl Fn(f32) -> f32> {
+        let thin_film = self.thin_film?;
+        let albedo = self.albedo;
+        let cos_theta = -r_in.direction().normalize().dot(rec.normal);
+        Some(move |lambda| {
+            // The metal reflecting the albedo at the wavelength `lambda` head on.
+            let r = SampledSpectrum::colour_at_wavelength(albedo, lambda);
+            let (eta, k) = fresnel::conductor_index(r, r);
+            thin_film.reflectance(cos_theta, lambda, eta, k)
+        })
+    }
 }
 
 impl Material for Metal {
-    fn scatter(
-        &self,
-        r_in: &Ray,
-        rec: &HitRecord,
-        attenuation: &mut Colour,
-        scattered: &mut Ray,
-    ) -> bool {
-        let mut reflected = reflect(&r_in.direction(), &rec.normal);
-        reflected = reflected.normalize() + (self.fuzz * random_unit_vector());
-        *scattered = Ray::new(rec.p, reflected);
-        *attenuation = self.albedo;
-        return true;
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord, _sampler: &mut dyn Sampler) -> Option<Scatter> {
+        let reflected = reflect(r_in.direction(), &rec.normal).normalize();
+        if self.fuzz > 0.0 {
+            // A fuzzy reflection can be evaluated in any direction, so it can be combined with
+            // light sampling.
+            return Some(Scatter::Pdf(Box::new(FuzzPdf {
+                reflected,
+                fuzz: self.fuzz,
+            })));
+        }
+        let ray = Ray::new(rec.p, reflected);
+        match self.film_reflectance(r_in, rec) {
+            // Every wavelength is reflected along the same ray, each by its own amount.
+            Some(reflectance) => Some(Scatter::SpectralSpecular {
+                reflectance: Box::new(reflectance),
+                ray,
+            }),
+            None => Some(Scatter::Specular {
+                attenuation: self.albedo,
+                ray,
+            }),
+        }
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Colour {
+        // Light is reflected with exactly the density directions are sampled with, so sampled
+        // directions are weighted by the albedo. Directions into the surface are absorbed, and a
+        // mirror reflects no light in any direction but its specular one.
+        if self.fuzz <= 0.0 || scattered.direction().dot(rec.normal) <= 0.0 {
+            return Colour::default();
+        }
+        let pdf = FuzzPdf {
+            reflected: reflect(r_in.direction(), &rec.normal).normalize(),
+            fuzz: self.fuzz,
+        };
+        pdf.value(*scattered.direction()) * self.reflectance(r_in, rec)
+    }
+
+    fn albedo(&self, _rec: &HitRecord) -> Colour {
+        self.albedo
+    }
+
+    fn is_dispersive(&self, _rec: &HitRecord) -> bool {
+        // A fuzzy coated metal is only evaluated at the hero wavelength, while a smooth one
+        // reflects every wavelength a path carries.
+        self.thin_film.is_some() && self.fuzz > 0.0
+    }
+}
+
+/// The density of directions towards a point chosen uniformly on a sphere of radius `fuzz`
+/// around the tip of the mirror reflection direction.
+struct FuzzPdf {
+    /// Unit mirror reflection direction.
+    reflected: Vec3,
+    /// Radius of the sphere around the tip of `reflected`, at most one.
+    fuzz: f32,
+}
+
+impl Pdf for FuzzPdf {
+    fn value(&self, direction: Vec3) -> f32 {
+        // Sum the density of every point where the direction passes through the sphere,
+        // converted from area on the sphere to solid angle.
+        let d = direction.normalize();
+        let b = d.dot(self.reflected);
+        let discriminant = b * b - (1.0 - self.fuzz * self.fuzz);
+        // Allow for rounding error in directions that graze the sphere.
+        if discriminant < -1e-6 {
+            return 0.0;
+        }
+        let sqrtd = discriminant.max(0.0).sqrt();
+        [b - sqrtd, b + sqrtd]
+            .into_iter()
+            .filter(|&t| t > 1e-6)
+            .map(|t| {
+                let normal = (t * d - self.reflected) / self.fuzz;
+                let cosine = d.dot(normal).abs().max(1e-6);
+                t * t / (4.0 * PI * self.fuzz * self.fuzz * cosine)
+            })
+            .sum()
+    }
+
+    fn generate(&self, u: Vec2) -> Vec3 {
+        self.reflected + self.fuzz * sample_unit_vector(u)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        material::test_util::{grid, hit},
+        sampler::IndependentSampler,
+        Point3,
+    };
+
+    #[test]
+    fn vanishing_films_keep_the_albedo_head_on() {
+        let albedo = Colour::new(0.8, 0.5, 0.2);
+        let coated = Metal::new(&albedo, 0.0).thin_film(ThinFilm::new(0.0, 1.4));
+        let rec = hit(true);
+        let r_in = Ray::new(Point3::new(0.0, 0.0, 1.0), -Vec3::Z);
+        let Some(Scatter::SpectralSpecular { reflectance, .. }) =
+            coated.scatter(&r_in, &rec, &mut IndependentSampler)
+        else {
+            panic!("a smooth coated metal scatters specularly");
+        };
+        let colour = reflectance_colour(reflectance);
+        let d = colour - albedo;
+        assert!(
+            d.r().abs().max(d.g().abs()).max(d.b().abs()) < 0.02,
+            "{colour}"
+        );
+    }
+
+    #[test]
+    fn smooth_coated_metals_reflect_each_wavelength() {
+        let coated =
+            Metal::new(&Colour::new(0.5, 0.5, 0.5), 0.0).thin_film(ThinFilm::new(350.0, 1.45));
+        let rec = hit(true);
+        // Spectral paths keep every wavelength, as they are all reflected along the same ray.
+        assert!(!coated.is_dispersive(&rec));
+        let r_in = Ray::new(Point3::new(0.0, 0.0, 1.0), -Vec3::Z).with_wavelength(450.0);
+        let Some(Scatter::SpectralSpecular { reflectance, .. }) =
+            coated.scatter(&r_in, &rec, &mut IndependentSampler)
+        else {
+            panic!("a smooth coated metal scatters specularly");
+        };
+        assert!((reflectance(425.0) - reflectance(525.0)).abs() > 0.1);
+    }
+
+    #[test]
+    fn fuzz_pdf_matches_generated_directions() {
+        for fuzz in [0.1, 0.5, 1.0] {
+            let pdf = FuzzPdf {
+                reflected: Vec3::new(0.0, 0.6, 0.8),
+                fuzz,
+            };
+            // Averaging 1 / density over generated directions estimates the solid angle of the
+            // cone of directions that pass through the sphere.
+            let n = 256;
+            let solid_angle = grid(n)
+                .map(|u| 1.0 / pdf.value(pdf.generate(u)))
+                .sum::<f32>()
+                / (n * n) as f32;
+            let cone = 2.0 * PI * (1.0 - (1.0 - fuzz * fuzz).sqrt());
+            assert!((solid_angle / cone - 1.0).abs() < 0.01);
+        }
     }
 }
@@ -0,0 +1,306 @@
+use crate::{pdf::Pdf, util::Onb, Vec2, Vec3};
+
+use super::fresnel;
+use std::f32::consts::PI;
+
+/// The Trowbridge-Reitz (GGX) distribution of microfacet normals, with Smith masking and
+/// shadowing.
+///
+/// Directions are given in a local frame where the macroscopic surface normal is `+z`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct TrowbridgeReitz {
+    /// Width of the distribution of microfacet slopes.
+    alpha: f32,
+}
+
+impl TrowbridgeReitz {
+    /// Creates a distribution from a perceptual roughness in `[0, 1]`, whose square is the slope
+    /// width, so that the apparent blur changes evenly with the roughness.
+    pub(super) fn from_roughness(roughness: f32) -> Self {
+        let roughness = roughness.clamp(0.0, 1.0);
+        Self {
+            alpha: roughness * roughness,
+        }
+    }
+
+    /// Whether the surface is so smooth that it should be treated as a perfect mirror, as the
+    /// distribution becomes too peaked to evaluate accurately.
+    pub(super) fn is_smooth(&self) -> bool {
+        self.alpha < 1e-3
+    }
+
+    /// Density of microfacets with normal `wm`, per unit solid angle and projected area.
+    pub(super) fn d(&self, wm: Vec3) -> f32 {
+        let cos2_theta = wm.z * wm.z;
+        if cos2_theta <= 0.0 {
+            return 0.0;
+        }
+        let tan2_theta = (1.0 - cos2_theta).max(0.0) / cos2_theta;
+        let alpha2 = self.alpha * self.alpha;
+        let e = 1.0 + tan2_theta / alpha2;
+        1.0 / (PI * alpha2 * cos2_theta * cos2_theta * e * e)
+    }
+
+    /// Smith's auxiliary function, the ratio of hidden to visible microfacet area seen from `w`.
+    fn lambda(&self, w: Vec3) -> f32 {
+        let cos2_theta = w.z * w.z;
+        if cos2_theta <= 0.0 {
+            return f32::INFINITY;
+        }
+        let tan2_theta = (1.0 - cos2_theta).max(0.0) / cos2_theta;
+        ((1.0 + self.alpha * self.alpha * tan2_theta).sqrt() - 1.0) / 2.0
+    }
+
+    /// Fraction of the microfacets that are visible from `w`.
+    pub(super) fn g1(&self, w: Vec3) -> f32 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    /// Fraction of the microfacets that are visible from both `wo` and `wi`.
+    pub(super) fn g(&self, wo: Vec3, wi: Vec3) -> f32 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    /// Density of the microfacet normals visible from `w`, per unit solid angle.
+    pub(super) fn visible_d(&self, w: Vec3, wm: Vec3) -> f32 {
+        self.g1(w) / w.z.abs() * self.d(wm) * w.dot(wm).abs()
+    }
+
+    /// Maps a uniform sample in `[0, 1)²` to a microfacet normal visible from `w`, distributed
+    /// according to [`TrowbridgeReitz::visible_d`].
+    pub(super) fn sample_visible(&self, w: Vec3, u: Vec2) -> Vec3 {
+        // Stretch the view direction into the configuration where the distribution is a
+        // hemisphere, sample the visible projected area of the hemisphere, and unstretch.
+        let mut wh = Vec3::new(self.alpha * w.x, self.alpha * w.y, w.z).normalize();
+        if wh.z < 0.0 {
+            wh = -wh;
+        }
+        let t1 = if wh.z < 0.99999 {
+            Vec3::Z.cross(wh).normalize()
+        } else {
+            Vec3::X
+        };
+        let t2 = wh.cross(t1);
+
+        // A uniform point on the unit disk, squashed onto the half of it that is visible.
+        let r = u.x.sqrt();
+        let phi = 2.0 * PI * u.y;
+        let p1 = r * phi.cos();
+        let p2 = r * phi.sin();
+        let s = 0.5 * (1.0 + wh.z);
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).max(0.0).sqrt() + s * p2;
+
+        let pz = (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt();
+        let nh = p1 * t1 + p2 * t2 + pz * wh;
+        Vec3::new(self.alpha * nh.x, self.alpha * nh.y, nh.z.max(1e-6)).normalize()
+    }
+}
+
+/// Mirror reflection of `wo` about the microfacet normal `wm`.
+pub(super) fn reflect(wo: Vec3, wm: Vec3) -> Vec3 {
+    2.0 * wo.dot(wm) * wm - wo
+}
+
+/// The density of directions found by reflecting off microfacet normals sampled from those
+/// visible from the outgoing direction.
+pub(super) struct MicrofacetReflectionPdf {
+    /// Shading frame, with the surface normal as its `w` axis.
+    frame: Onb,
+    /// Unit direction towards where the light is scattered, in the shading frame.
+    wo: Vec3,
+    /// Distribution of microfacet normals.
+    distribution: TrowbridgeReitz,
+}
+
+impl MicrofacetReflectionPdf {
+    /// Creates the density of reflections of light leaving along `wo` in the shading frame
+    /// `frame`.
+    pub(super) fn new(frame: Onb, wo: Vec3, distribution: TrowbridgeReitz) -> Self {
+        Self {
+            frame,
+            wo,
+            distribution,
+        }
+    }
+}
+
+impl Pdf for MicrofacetReflectionPdf {
+    fn value(&self, direction: Vec3) -> f32 {
+        let wi = self.frame.to_local(direction.normalize());
+        if wi.z <= 0.0 || self.wo.z <= 0.0 {
+            return 0.0;
+        }
+        let wm = (self.wo + wi).normalize();
+        // Change of variables from the microfacet normal to the reflected direction.
+        self.distribution.visible_d(self.wo, wm) / (4.0 * self.wo.dot(wm))
+    }
+
+    fn generate(&self, u: Vec2) -> Vec3 {
+        let wm = self.distribution.sample_visible(self.wo, u);
+        self.frame.transform(reflect(self.wo, wm))
+    }
+}
+
+/// Refraction of `wo` through the microfacet normal `wm`, on the same side as `wo`, into a medium
+/// whose refractive index relative to that of `wo`'s side is `eta`. `None` if the light is
+/// totally internally reflected instead.
+pub(super) fn refract(wo: Vec3, wm: Vec3, eta: f32) -> Option<Vec3> {
+    let cos_o = wo.dot(wm);
+    let sin2_t = (1.0 - cos_o * cos_o).max(0.0) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return None;
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+    Some(-wo / eta + (cos_o / eta - cos_t) * wm)
+}
+
+/// The microfacet normal that scatters `wo` into `wi` at a rough interface into a medium with
+/// relative refractive index `eta`, facing `+z`, or `None` if no facet visible from both
+/// directions does.
+fn dielectric_normal(wo: Vec3, wi: Vec3, eta: f32) -> Option<Vec3> {
+    // Reflection uses the half vector, and refraction its generalisation by Walter et al.
+    let wm = if wi.z > 0.0 { wo + wi } else { eta * wi + wo };
+    let wm = wm.try_normalize()?;
+    let wm = if wm.z < 0.0 { -wm } else { wm };
+    // Facets seen from behind by either direction cannot scatter between them.
+    if wm.dot(wi) * wi.z < 0.0 || wm.dot(wo) * wo.z < 0.0 {
+        return None;
+    }
+    Some(wm)
+}
+
+/// Light scattered from `wi` into `wo` by a rough interface into a medium with relative
+/// refractive index `eta`, both in the local frame on the side of `wo`: the BSDF times the
+/// cosine of `wi`.
+///
+/// Like [`Dielectric`](super::Dielectric), the change in radiance as refraction narrows or widens
+/// a beam is left out, as it cancels once light leaves the object again.
+pub(super) fn dielectric_eval(distribution: &TrowbridgeReitz, eta: f32, wo: Vec3, wi: Vec3) -> f32 {
+    if wo.z <= 0.0 || wi.z == 0.0 {
+        return 0.0;
+    }
+    let Some(wm) = dielectric_normal(wo, wi, eta) else {
+        return 0.0;
+    };
+    let f = fresnel::dielectric(wo.dot(wm), eta);
+    let d = distribution.d(wm);
+    let g = distribution.g(wo, wi);
+    if wi.z > 0.0 {
+        d * g * f / (4.0 * wo.z)
+    } else {
+        let denom = (wi.dot(wm) + wo.dot(wm) / eta).powi(2);
+        (1.0 - f) * d * g * (wi.dot(wm) * wo.dot(wm) / (wo.z * denom)).abs()
+    }
+}
+
+/// The density of directions found by sampling a microfacet normal visible from the outgoing
+/// direction, then either reflecting off it or refracting through it in proportion to its
+/// Fresnel reflectance.
+pub(super) struct MicrofacetDielectricPdf {
+    /// Shading frame, with the normal on the side of the outgoing direction as its `w` axis.
+    frame: Onb,
+    /// Unit direction towards where the light is scattered, in the shading frame.
+    wo: Vec3,
+    /// Refractive index of the far side of the interface relative to the side of `wo`.
+    eta: f32,
+    /// Distribution of microfacet normals.
+    distribution: TrowbridgeReitz,
+    /// Uniform sample choosing between reflection and refraction, as [`Pdf::generate`] only gets
+    /// two dimensions for picking the facet.
+    choice: f32,
+}
+
+impl MicrofacetDielectricPdf {
+    /// Creates the density of scattering light leaving along `wo` in the shading frame `frame`,
+    /// choosing between reflection and refraction with the uniform sample `choice`.
+    pub(super) fn new(
+        frame: Onb,
+        wo: Vec3,
+        eta: f32,
+        distribution: TrowbridgeReitz,
+        choice: f32,
+    ) -> Self {
+        Self {
+            frame,
+            wo,
+            eta,
+            distribution,
+            choice,
+        }
+    }
+}
+
+impl Pdf for MicrofacetDielectricPdf {
+    fn value(&self, direction: Vec3) -> f32 {
+        let wo = self.wo;
+        let wi = self.frame.to_local(direction.normalize());
+        if wo.z <= 0.0 || wi.z == 0.0 {
+            return 0.0;
+        }
+        let Some(wm) = dielectric_normal(wo, wi, self.eta) else {
+            return 0.0;
+        };
+        let f = fresnel::dielectric(wo.dot(wm), self.eta);
+        let visible_d = self.distribution.visible_d(wo, wm);
+        // Change of variables from the microfacet normal to the scattered direction.
+        if wi.z > 0.0 {
+            f * visible_d / (4.0 * wo.dot(wm))
+        } else {
+            let denom = (wi.dot(wm) + wo.dot(wm) / self.eta).powi(2);
+            (1.0 - f) * visible_d * wi.dot(wm).abs() / denom
+        }
+    }
+
+    fn generate(&self, u: Vec2) -> Vec3 {
+        let wm = self.distribution.sample_visible(self.wo, u);
+        let f = fresnel::dielectric(self.wo.dot(wm), self.eta);
+        let wi = match refract(self.wo, wm, self.eta) {
+            Some(refracted) if self.choice >= f => refracted,
+            _ => reflect(self.wo, wm),
+        };
+        self.frame.transform(wi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::test_util::{grid, uniform_hemisphere};
+
+    /// Estimates the integral of `f` over the `+z` hemisphere.
+    fn integrate_hemisphere(f: impl Fn(Vec3) -> f32) -> f32 {
+        let samples: Vec<Vec2> = grid(512).collect();
+        let sum: f32 = samples.iter().map(|&u| f(uniform_hemisphere(u))).sum();
+        sum * 2.0 * PI / samples.len() as f32
+    }
+
+    #[test]
+    fn projected_microfacet_area_is_one() {
+        // The microfacets cover exactly the macroscopic surface when projected onto it.
+        for roughness in [0.3, 0.6, 1.0] {
+            let distribution = TrowbridgeReitz::from_roughness(roughness);
+            let area = integrate_hemisphere(|wm| distribution.d(wm) * wm.z);
+            assert!((area - 1.0).abs() < 0.02, "roughness {roughness}: {area}");
+        }
+    }
+
+    #[test]
+    fn reflection_pdf_matches_generated_directions() {
+        for roughness in [0.3, 0.7] {
+            let distribution = TrowbridgeReitz::from_roughness(roughness);
+            let wo = Vec3::new(0.6, 0.0, 0.8);
+            let pdf = MicrofacetReflectionPdf::new(Onb::new(Vec3::Z), wo, distribution);
+            // Some reflections go below the surface, where the density is zero, so the density
+            // integrates to the fraction of generated directions that stay above it.
+            let integral = integrate_hemisphere(|wi| pdf.value(wi));
+            let samples: Vec<Vec2> = grid(512).collect();
+            let above = samples.iter().filter(|&&u| pdf.generate(u).z > 0.0).count();
+            let fraction = above as f32 / samples.len() as f32;
+            assert!(fraction > 0.5);
+            assert!(
+                (integral - fraction).abs() < 0.02,
+                "roughness {roughness}: {integral} {fraction}"
+            );
+        }
+    }
+}
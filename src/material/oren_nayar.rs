@@ -0,0 +1,153 @@
+use crate::{
+    hittable::HitRecord, pdf::CosinePdf, sampler::Sampler, texture::Texture, util::Onb, Colour, Ray,
+};
+use std::{f32::consts::PI, rc::Rc};
+
+use super::{Material, Scatter};
+
+/// A rough diffuse surface, such as clay, concrete or fabric, using the Oren-Nayar model.
+///
+/// The surface is made of tiny V-shaped grooves whose sides each reflect light like
+/// [`Lambertian`](super::Lambertian), with slopes spread out by the roughness `sigma`. The
+/// grooves facing the light are also the ones facing back at it, so rough surfaces look flatter
+/// than ideal diffuse ones and reflect more light back towards where it comes from, as the full
+/// moon does. At zero roughness it is the same as [`Lambertian`](super::Lambertian).
+///
+/// Scattered rays are sampled from a cosine distribution, like for an ideal diffuse surface,
+/// which the model stays close to.
+///
+/// # Example
+/// ```
+/// use ray_tracing::{material::OrenNayar, texture::Checker, Colour};
+///
+/// let clay = OrenNayar::new(Colour::new(0.7, 0.4, 0.3), 20.0);
+/// let tiles = OrenNayar::new(
+///     Checker::new(0.5, Colour::new(0.8, 0.8, 0.8), Colour::new(0.3, 0.3, 0.3)),
+///     Checker::new(0.5, 10.0, 40.0),
+/// );
+/// ```
+pub struct OrenNayar {
+    /// Colour of the facets.
+    albedo: Rc<dyn Texture>,
+    /// Standard deviation of the facet slopes, in degrees.
+    sigma: Rc<dyn Texture>,
+}
+
+impl OrenNayar {
+    /// Creates a new rough diffuse material.
+    ///
+    /// # Parameters
+    /// - `albedo`: The colour of the surface.
+    /// - `sigma`: The standard deviation of the facet slope angles in degrees, from 0 for an
+    ///   ideal diffuse surface to around 40 for very rough surfaces. Read from the red channel.
+    ///
+    /// # Returns
+    /// A new `OrenNayar` material.
+    pub fn new(albedo: impl Texture + 'static, sigma: impl Texture + 'static) -> Self {
+        Self {
+            albedo: Rc::new(albedo),
+            sigma: Rc::new(sigma),
+        }
+    }
+}
+
+impl Material for OrenNayar {
+    fn scatter(&self, _r_in: &Ray, rec: &HitRecord, _sampler: &mut dyn Sampler) -> Option<Scatter> {
+        Some(Scatter::Pdf(Box::new(CosinePdf::new(rec.normal))))
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Colour {
+        let frame = Onb::new(rec.normal);
+        let wo = frame.to_local(-r_in.direction().normalize());
+        let wi = frame.to_local(scattered.direction().normalize());
+        if wi.z <= 0.0 || wo.z <= 0.0 {
+            return Colour::default();
+        }
+
+        let sigma = self.sigma.value(rec).r().max(0.0).to_radians();
+        let sigma2 = sigma * sigma;
+        let a = 1.0 - sigma2 / (2.0 * (sigma2 + 0.33));
+        let b = 0.45 * sigma2 / (sigma2 + 0.09);
+
+        let sin_theta_i = (1.0 - wi.z * wi.z).max(0.0).sqrt();
+        let sin_theta_o = (1.0 - wo.z * wo.z).max(0.0).sqrt();
+        // The cosine of the azimuthal angle between the directions, times both sines.
+        let cos_phi_sines = wi.x * wo.x + wi.y * wo.y;
+        // Sine of the larger of the two polar angles and tangent of the smaller.
+        let (sin_alpha, tan_beta) = if wi.z > wo.z {
+            (sin_theta_o, sin_theta_i / wi.z)
+        } else {
+            (sin_theta_i, sin_theta_o / wo.z)
+        };
+        let retro = if cos_phi_sines > 0.0 && sin_theta_i > 0.0 && sin_theta_o > 0.0 {
+            cos_phi_sines / (sin_theta_i * sin_theta_o) * sin_alpha * tan_beta
+        } else {
+            0.0
+        };
+
+        self.albedo.value(rec) * ((a + b * retro) * wi.z / PI)
+    }
+
+    fn albedo(&self, rec: &HitRecord) -> Colour {
+        self.albedo.value(rec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        material::{
+            test_util::{assert_scatters_no_more_light_than_arrives, hit},
+            Lambertian,
+        },
+        Point3, Vec3,
+    };
+
+    /// Fraction of the light arriving from `direction` reflected into it, the ratio of the
+    /// material's response to that of an ideal diffuse surface.
+    fn backscatter(mat: &dyn Material, direction: Vec3) -> f32 {
+        let r_in = Ray::new(Point3::ZERO + direction, -direction);
+        let scattered = Ray::new(Point3::ZERO, direction);
+        mat.eval(&r_in, &hit(true), &scattered).r() / (direction.normalize().z / PI)
+    }
+
+    #[test]
+    fn smooth_surfaces_are_lambertian() {
+        let white = Colour::new(1.0, 1.0, 1.0);
+        let smooth = OrenNayar::new(white, 0.0);
+        let lambertian = Lambertian::new(&white);
+        let r_in = Ray::new(Point3::new(-1.0, 0.0, 1.0), Vec3::new(1.0, 0.0, -1.0));
+        for direction in [Vec3::Z, Vec3::new(1.0, 0.0, 1.0), Vec3::new(-1.0, 2.0, 0.5)] {
+            let scattered = Ray::new(Point3::ZERO, direction);
+            let expected = lambertian.eval(&r_in, &hit(true), &scattered);
+            let value = smooth.eval(&r_in, &hit(true), &scattered);
+            assert!((value - expected).luminance().abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn rough_surfaces_reflect_light_back() {
+        let rough = OrenNayar::new(Colour::new(1.0, 1.0, 1.0), 30.0);
+        let grazing = Vec3::new(1.0, 0.0, 0.3);
+        let mirror = Vec3::new(-1.0, 0.0, 0.3);
+        let r_in = Ray::new(Point3::ZERO + grazing, -grazing);
+        let towards_mirror = rough.eval(&r_in, &hit(true), &Ray::new(Point3::ZERO, mirror));
+        // Light arriving at a grazing angle is mostly sent back where it came from.
+        assert!(backscatter(&rough, grazing) > 1.0);
+        assert!(towards_mirror.r() / (mirror.normalize().z / PI) < 1.0);
+    }
+
+    #[test]
+    fn scatters_no_more_light_than_arrives() {
+        for sigma in [10.0, 30.0, 60.0] {
+            let mat = OrenNayar::new(Colour::new(1.0, 1.0, 1.0), sigma);
+            for r_in in [Vec3::new(0.0, 0.0, -1.0), Vec3::new(0.8, 0.0, -0.6)] {
+                let r_in = Ray::new(Point3::ZERO - r_in, r_in);
+                // Light bouncing between facets is left out, so rough surfaces are a little
+                // darker than their albedo.
+                assert_scatters_no_more_light_than_arrives(&mat, &r_in, &hit(true), 0.6);
+            }
+        }
+    }
+}
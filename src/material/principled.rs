@@ -0,0 +1,576 @@
+use crate::{
+    hittable::HitRecord,
+    pdf::{CosinePdf, Pdf},
+    sampler::Sampler,
+    texture::Texture,
+    util::Onb,
+    Colour, Ray, Vec2, Vec3,
+};
+use std::{f32::consts::PI, rc::Rc};
+
+use super::{
+    fresnel,
+    microfacet::{self, MicrofacetDielectricPdf, MicrofacetReflectionPdf, TrowbridgeReitz},
+    Material, Scatter,
+};
+
+/// Smallest roughness the material renders, as it has no perfectly smooth mirror path.
+const MIN_ROUGHNESS: f32 = 0.05;
+
+/// Reflectance head on of the clear coat, that of a varnish with a refractive index of 1.5.
+const CLEARCOAT_REFLECTANCE: f32 = 0.04;
+
+/// A general purpose material combining the layers of most real surfaces, in the style of the
+/// Disney principled BSDF and glTF's metallic-roughness model.
+///
+/// Every parameter is a [`Texture`], so it can vary over the surface; scalar parameters read the
+/// red channel, and plain `f32`s and [`Colour`]s can be used for uniform values. The surface is a
+/// blend of:
+/// - a diffuse base of the base colour, with a sheen at grazing angles, as on cloth,
+/// - a GGX specular reflection, tinted by the base colour for metals,
+/// - a GGX transmission through the surface, tinted by the base colour as light enters, as for
+///   coloured glass,
+/// - a clear coat on top, such as a car's varnish, with its own roughness.
+///
+/// Most factors of glTF materials carry over unchanged: `baseColorFactor` is the base colour,
+/// `metallicFactor` and `roughnessFactor` are the metallic and roughness, `emissiveFactor` is
+/// the emission, and the factors of `KHR_materials_sheen`, `KHR_materials_clearcoat` and
+/// `KHR_materials_transmission` give the sheen, clear coat and transmission. The specular is
+/// Disney's rather than `KHR_materials_specular`'s: it reflects `0.08 * specular` head on, so a
+/// glTF material reflecting `((ior - 1) / (ior + 1))² * specularFactor` converts to a specular
+/// of `12.5 * ((ior - 1) / (ior + 1))² * specularFactor`, which is 0.5 for glTF's defaults.
+/// `KHR_materials_ior` and `specularColorFactor` have no equivalent; transmission refracts with
+/// the index reflecting as much head on as the specular. Hit records carry no texture
+/// coordinates and there are no image textures, so glTF's image textures cannot be used;
+/// parameters can only vary over the surface with procedural textures such as
+/// [`Checker`](crate::texture::Checker), whose channels can be shared between parameters as
+/// glTF's packed textures are.
+///
+/// # Example
+/// ```
+/// use ray_tracing::{
+///     material::Principled,
+///     texture::{Channel, Scale, Texture},
+///     Colour,
+/// };
+/// use std::rc::Rc;
+///
+/// // Metallic and roughness read from two channels of a texture shared by both parameters.
+/// let metallic_roughness: Rc<dyn Texture> = Rc::new(Colour::new(0.0, 0.6, 1.0));
+/// let roughness_factor = Colour::new(0.5, 0.5, 0.5);
+/// let painted_metal = Principled::new(Colour::new(0.9, 0.6, 0.2))
+///     .metallic(Channel::blue(metallic_roughness.clone()))
+///     .roughness(Scale::new(Channel::green(metallic_roughness), roughness_factor))
+///     .clearcoat(1.0)
+///     .clearcoat_roughness(0.1);
+///
+/// let frosted_glass = Principled::new(Colour::new(0.9, 1.0, 0.95))
+///     .roughness(0.3)
+///     .transmission(1.0);
+/// ```
+pub struct Principled {
+    /// Colour of the diffuse base, the reflections of metals and the light transmitted.
+    base_colour: Rc<dyn Texture>,
+    /// How much the surface is a metal rather than a dielectric, from 0 to 1.
+    metallic: Rc<dyn Texture>,
+    /// Perceptual roughness of the specular reflection and transmission, from 0 to 1.
+    roughness: Rc<dyn Texture>,
+    /// Strength of the specular reflection of dielectrics, where 0.5 is a reflectance head on of
+    /// 4%.
+    specular: Rc<dyn Texture>,
+    /// How much the specular reflection of dielectrics is tinted by the base colour, from 0 to 1.
+    specular_tint: Rc<dyn Texture>,
+    /// Colour of the sheen at grazing angles.
+    sheen: Rc<dyn Texture>,
+    /// Strength of the clear coat, from 0 to 1.
+    clearcoat: Rc<dyn Texture>,
+    /// Perceptual roughness of the clear coat, from 0 to 1.
+    clearcoat_roughness: Rc<dyn Texture>,
+    /// How much of the light passing into a dielectric is transmitted rather than diffused, from
+    /// 0 to 1.
+    transmission: Rc<dyn Texture>,
+    /// Radiance emitted from the front face.
+    emission: Rc<dyn Texture>,
+}
+
+impl Principled {
+    /// Creates a new rough, non-metallic material of the given base colour, with every other
+    /// parameter at its default.
+    ///
+    /// # Parameters
+    /// - `base_colour`: The colour of the surface.
+    ///
+    /// # Returns
+    /// A new `Principled` material.
+    pub fn new(base_colour: impl Texture + 'static) -> Self {
+        Self {
+            base_colour: Rc::new(base_colour),
+            metallic: Rc::new(0.0),
+            roughness: Rc::new(0.5),
+            specular: Rc::new(0.5),
+            specular_tint: Rc::new(0.0),
+            sheen: Rc::new(0.0),
+            clearcoat: Rc::new(0.0),
+            clearcoat_roughness: Rc::new(0.1),
+            transmission: Rc::new(0.0),
+            emission: Rc::new(0.0),
+        }
+    }
+
+    /// How much the surface is a metal, from 0 for a dielectric such as plastic to 1 for a metal
+    /// whose reflections are tinted by the base colour. Defaults to 0.
+    pub fn metallic(self, metallic: impl Texture + 'static) -> Self {
+        Self {
+            metallic: Rc::new(metallic),
+            ..self
+        }
+    }
+
+    /// Roughness of the specular reflection and transmission, from 0 for polished to 1 for
+    /// matte. Roughness below 0.05 is treated as 0.05. Defaults to 0.5.
+    pub fn roughness(self, roughness: impl Texture + 'static) -> Self {
+        Self {
+            roughness: Rc::new(roughness),
+            ..self
+        }
+    }
+
+    /// Strength of the specular reflection of dielectrics, where 0.5 reflects 4% of light head
+    /// on, like most plastics and glass, and 1 reflects 8%. It also sets the refractive index
+    /// for transmission. Defaults to 0.5.
+    pub fn specular(self, specular: impl Texture + 'static) -> Self {
+        Self {
+            specular: Rc::new(specular),
+            ..self
+        }
+    }
+
+    /// How much the specular reflection of dielectrics takes the hue of the base colour, from 0
+    /// to 1. Defaults to 0.
+    pub fn specular_tint(self, specular_tint: impl Texture + 'static) -> Self {
+        Self {
+            specular_tint: Rc::new(specular_tint),
+            ..self
+        }
+    }
+
+    /// Colour of a soft reflection at grazing angles, as seen on cloth. Defaults to black.
+    pub fn sheen(self, sheen: impl Texture + 'static) -> Self {
+        Self {
+            sheen: Rc::new(sheen),
+            ..self
+        }
+    }
+
+    /// Strength of a clear, colourless coat on top of the surface, from 0 to 1. Defaults to 0.
+    pub fn clearcoat(self, clearcoat: impl Texture + 'static) -> Self {
+        Self {
+            clearcoat: Rc::new(clearcoat),
+            ..self
+        }
+    }
+
+    /// Roughness of the clear coat, from 0 to 1. Roughness below 0.05 is treated as 0.05.
+    /// Defaults to 0.1.
+    pub fn clearcoat_roughness(self, clearcoat_roughness: impl Texture + 'static) -> Self {
+        Self {
+            clearcoat_roughness: Rc::new(clearcoat_roughness),
+            ..self
+        }
+    }
+
+    /// How much of the light entering a dielectric passes through it rather than being diffused,
+    /// from 0 for an opaque surface to 1 for glass. The light is tinted by the base colour as it
+    /// enters through the front face, and not again as it leaves, so a closed object is tinted
+    /// once. Defaults to 0.
+    pub fn transmission(self, transmission: impl Texture + 'static) -> Self {
+        Self {
+            transmission: Rc::new(transmission),
+            ..self
+        }
+    }
+
+    /// Radiance emitted from the front face, on top of any light scattered. Defaults to black.
+    pub fn emission(self, emission: impl Texture + 'static) -> Self {
+        Self {
+            emission: Rc::new(emission),
+            ..self
+        }
+    }
+
+    /// Evaluates the textures at the hit point `rec`.
+    fn params(&self, rec: &HitRecord) -> Params {
+        let scalar = |texture: &Rc<dyn Texture>| texture.value(rec).r().clamp(0.0, 1.0);
+        let roughness = |texture: &Rc<dyn Texture>| {
+            TrowbridgeReitz::from_roughness(scalar(texture).max(MIN_ROUGHNESS))
+        };
+
+        let base = self.base_colour.value(rec);
+        let white = Colour::new(1.0, 1.0, 1.0);
+        let luminance = base.luminance();
+        let hue = if luminance > 0.0 {
+            base * (1.0 / luminance)
+        } else {
+            white
+        };
+        let reflectance = (0.08 * self.specular.value(rec).r()).clamp(1e-4, 0.99);
+        let f0_dielectric = lerp(white, hue, scalar(&self.specular_tint)) * reflectance;
+        // The refractive index with the same reflectance head on.
+        let eta = (1.0 + reflectance.sqrt()) / (1.0 - reflectance.sqrt());
+
+        Params {
+            base,
+            metallic: scalar(&self.metallic),
+            distribution: roughness(&self.roughness),
+            f0_dielectric,
+            eta: if rec.front_face { eta } else { 1.0 / eta },
+            transmission_tint: if rec.front_face { base } else { white },
+            sheen: self.sheen.value(rec),
+            clearcoat: scalar(&self.clearcoat),
+            clearcoat_distribution: roughness(&self.clearcoat_roughness),
+            transmission: scalar(&self.transmission),
+        }
+    }
+
+    /// The density scattered rays are sampled from for light leaving along `r_in` reversed,
+    /// choosing between reflection and refraction by a facet with the uniform sample `choice`.
+    /// `None` if the surface scatters no light.
+    fn pdf(&self, r_in: &Ray, rec: &HitRecord, choice: f32) -> Option<LobePdf> {
+        let params = self.params(rec);
+        let frame = Onb::new(rec.normal);
+        let wo = frame.to_local(-r_in.direction().normalize());
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        // Weight each lobe by roughly how much light it scatters.
+        let [specular, diffuse, transmission, clearcoat] = params.lobe_weights(wo);
+        let lobes: Vec<(f32, Box<dyn Pdf>)> = vec![
+            (
+                specular,
+                Box::new(MicrofacetReflectionPdf::new(frame, wo, params.distribution)),
+            ),
+            (diffuse, Box::new(CosinePdf::new(rec.normal))),
+            (
+                transmission,
+                Box::new(MicrofacetDielectricPdf::new(
+                    frame,
+                    wo,
+                    params.eta,
+                    params.distribution,
+                    choice,
+                )),
+            ),
+            (
+                clearcoat,
+                Box::new(MicrofacetReflectionPdf::new(
+                    frame,
+                    wo,
+                    params.clearcoat_distribution,
+                )),
+            ),
+        ];
+        LobePdf::new(lobes)
+    }
+}
+
+impl Material for Principled {
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord, sampler: &mut dyn Sampler) -> Option<Scatter> {
+        let choice = sampler.get_1d();
+        let pdf = self.pdf(r_in, rec, choice)?;
+        Some(Scatter::Pdf(Box::new(pdf)))
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Colour {
+        let frame = Onb::new(rec.normal);
+        let wo = frame.to_local(-r_in.direction().normalize());
+        let wi = frame.to_local(scattered.direction().normalize());
+        self.params(rec).eval(wo, wi)
+    }
+
+    fn emitted(&self, _r_in: &Ray, rec: &HitRecord) -> Colour {
+        if rec.front_face {
+            self.emission.value(rec)
+        } else {
+            Colour::default()
+        }
+    }
+
+    fn albedo(&self, rec: &HitRecord) -> Colour {
+        self.base_colour.value(rec)
+    }
+}
+
+/// The parameters of a [`Principled`] material at one point.
+struct Params {
+    base: Colour,
+    metallic: f32,
+    /// Distribution of facet normals for the specular reflection and transmission.
+    distribution: TrowbridgeReitz,
+    /// Reflectance head on of the dielectric part of the surface.
+    f0_dielectric: Colour,
+    /// Refractive index of the far side of the surface relative to the side the ray arrives
+    /// from.
+    eta: f32,
+    /// Tint of the light transmitted through the surface, the base colour when it enters from
+    /// the front and white when it leaves.
+    transmission_tint: Colour,
+    sheen: Colour,
+    clearcoat: f32,
+    /// Distribution of facet normals for the clear coat.
+    clearcoat_distribution: TrowbridgeReitz,
+    transmission: f32,
+}
+
+impl Params {
+    /// Reflectance head on of the specular reflection, which is the base colour for metals.
+    fn f0(&self) -> Colour {
+        lerp(self.f0_dielectric, self.base, self.metallic)
+    }
+
+    /// Fraction of the light seen from `wo` that gets through the clear coat to the layers below.
+    fn below_clearcoat(&self, wo: Vec3) -> f32 {
+        1.0 - self.clearcoat * clearcoat_fresnel(wo.z)
+    }
+
+    /// Light scattered from `wi` into `wo`, both in the local shading frame: the BSDF times the
+    /// cosine of `wi`.
+    fn eval(&self, wo: Vec3, wi: Vec3) -> Colour {
+        if wo.z <= 0.0 || wi.z == 0.0 {
+            return Colour::default();
+        }
+        let below_clearcoat = self.below_clearcoat(wo);
+        let dielectric = 1.0 - self.metallic;
+
+        if wi.z < 0.0 {
+            let transmitted = microfacet::dielectric_eval(&self.distribution, self.eta, wo, wi);
+            let transmission = below_clearcoat * dielectric * self.transmission * transmitted;
+            return self.transmission_tint * transmission;
+        }
+
+        let wm = (wo + wi).normalize();
+        let cos_h = wo.dot(wm);
+        let microfacet = |distribution: &TrowbridgeReitz| {
+            distribution.d(wm) * distribution.g(wo, wi) / (4.0 * wo.z)
+        };
+
+        let specular = fresnel::schlick(cos_h, self.f0()) * microfacet(&self.distribution);
+        // Light not reflected by the dielectric's specular layer reaches the diffuse base.
+        let entering = Colour::new(1.0, 1.0, 1.0) - fresnel::schlick(wo.z, self.f0_dielectric);
+        let diffuse = self.base * entering * (wi.z / PI * dielectric * (1.0 - self.transmission));
+        let sheen = self.sheen * ((1.0 - cos_h).powi(5) * wi.z * dielectric);
+        let clearcoat =
+            self.clearcoat * clearcoat_fresnel(cos_h) * microfacet(&self.clearcoat_distribution);
+
+        (specular + diffuse + sheen) * below_clearcoat
+            + Colour::new(clearcoat, clearcoat, clearcoat)
+    }
+
+    /// Rough estimates of how much light each lobe scatters towards `wo`, in the order specular,
+    /// diffuse, transmission and clear coat.
+    fn lobe_weights(&self, wo: Vec3) -> [f32; 4] {
+        let below_clearcoat = self.below_clearcoat(wo);
+        let dielectric = 1.0 - self.metallic;
+        let entering = 1.0 - fresnel::schlick(wo.z, self.f0_dielectric).luminance();
+        let specular = fresnel::schlick(wo.z, self.f0()).luminance();
+        let diffuse = dielectric
+            * ((1.0 - self.transmission) * self.base.luminance() * entering
+                + self.sheen.luminance());
+        let transmission = dielectric * self.transmission * self.transmission_tint.luminance();
+        [
+            below_clearcoat * specular,
+            below_clearcoat * diffuse,
+            below_clearcoat * transmission,
+            self.clearcoat * clearcoat_fresnel(wo.z),
+        ]
+    }
+}
+
+/// Fraction of light reflected by the clear coat for light arriving at an angle with cosine
+/// `cos_theta` to its facet.
+fn clearcoat_fresnel(cos_theta: f32) -> f32 {
+    let f0 = CLEARCOAT_REFLECTANCE;
+    fresnel::schlick(cos_theta, Colour::new(f0, f0, f0)).r()
+}
+
+/// Linear interpolation from `a` at `t = 0` to `b` at `t = 1`.
+fn lerp(a: Colour, b: Colour, t: f32) -> Colour {
+    a * (1.0 - t) + b * t
+}
+
+/// A weighted mixture of the densities of each lobe of a [`Principled`] material.
+struct LobePdf {
+    /// Each lobe's density and the probability of sampling it, which sum to one.
+    lobes: Vec<(f32, Box<dyn Pdf>)>,
+}
+
+impl LobePdf {
+    /// Creates a mixture of `lobes`, sampling each in proportion to its weight. Lobes without
+    /// weight are dropped, and `None` is returned if there are none left.
+    fn new(lobes: Vec<(f32, Box<dyn Pdf>)>) -> Option<Self> {
+        let total: f32 = lobes.iter().map(|(weight, _)| weight.max(0.0)).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let lobes = lobes
+            .into_iter()
+            .filter(|(weight, _)| *weight > 0.0)
+            .map(|(weight, pdf)| (weight / total, pdf))
+            .collect();
+        Some(Self { lobes })
+    }
+}
+
+impl Pdf for LobePdf {
+    fn value(&self, direction: Vec3) -> f32 {
+        self.lobes
+            .iter()
+            .map(|(weight, pdf)| weight * pdf.value(direction))
+            .sum()
+    }
+
+    fn generate(&self, u: Vec2) -> Vec3 {
+        // As for `MixturePdf`, choose a lobe with the first dimension and stretch the part of it
+        // that chose the lobe back over [0, 1).
+        let mut start = 0.0;
+        for (k, (weight, pdf)) in self.lobes.iter().enumerate() {
+            if u.x < start + weight || k == self.lobes.len() - 1 {
+                let x = ((u.x - start) / weight).clamp(0.0, 1.0 - f32::EPSILON);
+                return pdf.generate(Vec2::new(x, u.y));
+            }
+            start += weight;
+        }
+        unreachable!("a lobe density always has a lobe")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        material::test_util::{assert_scatters_no_more_light_than_arrives, grid, hit},
+        sampler::sample_unit_vector,
+        Point3,
+    };
+
+    /// Luminance of the light arriving along `r_in` that is scattered, estimated by importance
+    /// sampling the material.
+    fn sampled(mat: &Principled, r_in: &Ray, rec: &HitRecord) -> f32 {
+        let samples: Vec<Vec2> = grid(128).collect();
+        let choices: Vec<f32> = (0..16).map(|k| (k as f32 + 0.5) / 16.0).collect();
+        let mut sum = 0.0;
+        for &choice in choices.iter() {
+            let pdf = mat.pdf(r_in, rec, choice).unwrap();
+            for &u in samples.iter() {
+                let scattered = Ray::new(rec.p, pdf.generate(u));
+                let pdf_value = pdf.value(*scattered.direction());
+                if pdf_value > 0.0 {
+                    sum += mat.eval(r_in, rec, &scattered).luminance() / pdf_value;
+                }
+            }
+        }
+        sum / (samples.len() * choices.len()) as f32
+    }
+
+    /// The same luminance, estimated by integrating uniformly over the sphere of directions.
+    fn integrated(mat: &Principled, r_in: &Ray, rec: &HitRecord) -> f32 {
+        let samples: Vec<Vec2> = grid(1024).collect();
+        let sum: f32 = samples
+            .iter()
+            .map(|&u| {
+                let scattered = Ray::new(rec.p, sample_unit_vector(u));
+                mat.eval(r_in, rec, &scattered).luminance()
+            })
+            .sum();
+        sum * 4.0 * PI / samples.len() as f32
+    }
+
+    #[test]
+    fn sampling_matches_scattering() {
+        let mat = Principled::new(Colour::new(0.8, 0.5, 0.3))
+            .metallic(0.3)
+            .roughness(0.5)
+            .sheen(Colour::new(0.5, 0.5, 0.5))
+            .clearcoat(0.5)
+            .clearcoat_roughness(0.4)
+            .transmission(0.5);
+        for front_face in [true, false] {
+            let rec = hit(front_face);
+            let r_in = Ray::new(Point3::ZERO, Vec3::new(0.5, 0.0, -0.8) * rec.normal.z);
+            let sampled = sampled(&mat, &r_in, &rec);
+            let integrated = integrated(&mat, &r_in, &rec);
+            assert!(
+                (sampled / integrated - 1.0).abs() < 0.03,
+                "{front_face}: {sampled} {integrated}"
+            );
+        }
+    }
+
+    #[test]
+    fn scatters_no_more_light_than_arrives() {
+        let white = Colour::new(1.0, 1.0, 1.0);
+        let materials = [
+            Principled::new(white),
+            Principled::new(white).metallic(1.0).roughness(0.3),
+            Principled::new(white).specular(1.0).roughness(0.1),
+            Principled::new(white).transmission(1.0).roughness(0.3),
+            Principled::new(white)
+                .clearcoat(1.0)
+                .clearcoat_roughness(0.3),
+        ];
+        for mat in materials.iter() {
+            let rec = hit(true);
+            let r_in = Ray::new(Point3::ZERO, Vec3::new(0.3, 0.0, -0.9));
+            assert_scatters_no_more_light_than_arrives(mat, &r_in, &rec, 0.5);
+        }
+    }
+
+    #[test]
+    fn transmission_tints_light_once() {
+        let glass = |base| Principled::new(base).roughness(0.3).transmission(1.0);
+        let (clear, green) = (
+            glass(Colour::new(1.0, 1.0, 1.0)),
+            glass(Colour::new(0.2, 0.8, 0.2)),
+        );
+        let transmitted = |mat: &Principled, front_face: bool| {
+            let rec = hit(front_face);
+            let r_in = Ray::new(Point3::ZERO, Vec3::new(0.3, 0.0, -0.9) * rec.normal.z);
+            let scattered = Ray::new(Point3::ZERO, Vec3::new(0.2, 0.0, -0.9) * rec.normal.z);
+            mat.eval(&r_in, &rec, &scattered)
+        };
+
+        // Light entering the glass takes its colour, but light leaving it is not tinted again.
+        let entering = transmitted(&clear, true);
+        let d = transmitted(&green, true) - entering * Colour::new(0.2, 0.8, 0.2);
+        assert!(d.r().abs().max(d.g().abs()).max(d.b().abs()) < 1e-5);
+        let leaving = transmitted(&clear, false);
+        assert!(entering.luminance() > 0.0 && leaving.luminance() > 0.0);
+        assert_eq!(transmitted(&green, false), leaving);
+    }
+
+    #[test]
+    fn default_specular_matches_gltf() {
+        // glTF's default dielectric reflects 4% head on, from an index of 1.5.
+        let params = Principled::new(Colour::new(0.5, 0.5, 0.5)).params(&hit(true));
+        assert!((params.f0_dielectric.g() - 0.04).abs() < 1e-6);
+        assert!((params.eta - 1.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn parameters_follow_textures() {
+        let packed = Rc::new(Colour::new(0.0, 0.2, 1.0));
+        let mat = Principled::new(Colour::new(0.9, 0.6, 0.2))
+            .metallic(crate::texture::Channel::blue(packed.clone()))
+            .roughness(crate::texture::Channel::green(packed))
+            .emission(Colour::new(2.0, 2.0, 2.0));
+        let params = mat.params(&hit(true));
+        assert_eq!(params.metallic, 1.0);
+        // Fully metallic surfaces reflect their base colour head on.
+        assert_eq!(params.f0(), Colour::new(0.9, 0.6, 0.2));
+        assert_eq!(params.distribution, TrowbridgeReitz::from_roughness(0.2));
+
+        let r_in = Ray::new(Point3::new(0.0, 0.0, 1.0), -Vec3::Z);
+        assert_eq!(mat.emitted(&r_in, &hit(true)), Colour::new(2.0, 2.0, 2.0));
+        assert_eq!(mat.emitted(&r_in, &hit(false)), Colour::default());
+    }
+}
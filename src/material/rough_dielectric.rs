@@ -0,0 +1,199 @@
+use crate::{
+    hittable::HitRecord,
+    sampler::Sampler,
+    util::{reflect, refract, Onb},
+    Colour, Ray,
+};
+
+use super::{
+    fresnel,
+    microfacet::{self, MicrofacetDielectricPdf, TrowbridgeReitz},
+    Material, Scatter,
+};
+
+/// Glass with a rough surface, such as frosted or etched glass, which blurs both the reflections
+/// and the view through it.
+///
+/// The surface is made of tiny smooth facets whose normals follow the GGX (Trowbridge-Reitz)
+/// distribution, each reflecting and refracting light with the exact Fresnel equations, as
+/// described by Walter et al. for rough dielectric interfaces. At zero roughness it behaves like
+/// [`Dielectric`](super::Dielectric).
+///
+/// # Example
+/// ```
+/// use ray_tracing::material::RoughDielectric;
+///
+/// let frosted_glass = RoughDielectric::new(1.5, 0.4);
+/// ```
+pub struct RoughDielectric {
+    /// Refractive index in vacuum or air, or the ratio of the material's refractive index over
+    /// the refractive index of the enclosing media
+    refraction_index: f32,
+    /// Distribution of facet normals.
+    distribution: TrowbridgeReitz,
+}
+
+impl RoughDielectric {
+    /// Creates a new rough dielectric.
+    ///
+    /// # Parameters
+    /// - `refraction_index`: The refractive index of the material, or its ratio over the
+    ///   refractive index of the enclosing medium, as for [`Dielectric`](super::Dielectric).
+    /// - `roughness`: How rough the surface is, from 0 for smooth glass to 1 for a very rough
+    ///   surface.
+    ///
+    /// # Returns
+    /// A new `RoughDielectric` material.
+    pub fn new(refraction_index: f32, roughness: f32) -> Self {
+        Self {
+            refraction_index,
+            distribution: TrowbridgeReitz::from_roughness(roughness),
+        }
+    }
+
+    /// Refractive index of the far side of the surface relative to the side the ray arrives
+    /// from.
+    fn eta(&self, rec: &HitRecord) -> f32 {
+        if rec.front_face {
+            self.refraction_index
+        } else {
+            1.0 / self.refraction_index
+        }
+    }
+}
+
+impl Material for RoughDielectric {
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord, sampler: &mut dyn Sampler) -> Option<Scatter> {
+        let eta = self.eta(rec);
+        let unit_direction = r_in.direction().normalize();
+        let u = sampler.get_1d();
+
+        if self.distribution.is_smooth() {
+            let cos_theta = f32::min(-unit_direction.dot(rec.normal), 1.0);
+            let direction = if fresnel::dielectric(cos_theta, eta) > u {
+                reflect(&unit_direction, &rec.normal)
+            } else {
+                refract(&unit_direction, &rec.normal, 1.0 / eta)
+            };
+            return Some(Scatter::Specular {
+                attenuation: Colour::new(1.0, 1.0, 1.0),
+                ray: Ray::new(rec.p, direction),
+            });
+        }
+
+        let frame = Onb::new(rec.normal);
+        let wo = frame.to_local(-unit_direction);
+        Some(Scatter::Pdf(Box::new(MicrofacetDielectricPdf::new(
+            frame,
+            wo,
+            eta,
+            self.distribution,
+            u,
+        ))))
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Colour {
+        let frame = Onb::new(rec.normal);
+        let wo = frame.to_local(-r_in.direction().normalize());
+        let wi = frame.to_local(scattered.direction().normalize());
+        let f = microfacet::dielectric_eval(&self.distribution, self.eta(rec), wo, wi);
+        Colour::new(f, f, f)
+    }
+
+    fn albedo(&self, _rec: &HitRecord) -> Colour {
+        Colour::new(1.0, 1.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        material::test_util::{assert_scatters_no_more_light_than_arrives, grid, hit},
+        pdf::Pdf,
+        sampler::sample_unit_vector,
+        Point3, Vec2, Vec3,
+    };
+    use std::f32::consts::PI;
+
+    /// Fractions of light arriving along `r_in` that are reflected and transmitted, estimated by
+    /// importance sampling the material.
+    fn sampled(mat: &RoughDielectric, r_in: &Ray, rec: &HitRecord) -> (f32, f32) {
+        let samples: Vec<Vec2> = grid(64).collect();
+        let choices: Vec<f32> = (0..64).map(|k| (k as f32 + 0.5) / 64.0).collect();
+        let (mut reflected, mut transmitted) = (0.0, 0.0);
+        for &choice in choices.iter() {
+            let pdf = MicrofacetDielectricPdf::new(
+                Onb::new(rec.normal),
+                Onb::new(rec.normal).to_local(-r_in.direction().normalize()),
+                mat.eta(rec),
+                mat.distribution,
+                choice,
+            );
+            for &u in samples.iter() {
+                let scattered = Ray::new(rec.p, pdf.generate(u));
+                let pdf_value = pdf.value(*scattered.direction());
+                if pdf_value <= 0.0 {
+                    continue;
+                }
+                let weight = mat.eval(r_in, rec, &scattered).r() / pdf_value;
+                if scattered.direction().dot(rec.normal) > 0.0 {
+                    reflected += weight;
+                } else {
+                    transmitted += weight;
+                }
+            }
+        }
+        let n = (samples.len() * choices.len()) as f32;
+        (reflected / n, transmitted / n)
+    }
+
+    /// The same fractions, estimated by integrating uniformly over the sphere of directions.
+    fn integrated(mat: &RoughDielectric, r_in: &Ray, rec: &HitRecord) -> (f32, f32) {
+        let samples: Vec<Vec2> = grid(1024).collect();
+        let (mut reflected, mut transmitted) = (0.0, 0.0);
+        for &u in samples.iter() {
+            let scattered = Ray::new(rec.p, sample_unit_vector(u));
+            let f = mat.eval(r_in, rec, &scattered).r();
+            if scattered.direction().dot(rec.normal) > 0.0 {
+                reflected += f;
+            } else {
+                transmitted += f;
+            }
+        }
+        let scale = 4.0 * PI / samples.len() as f32;
+        (reflected * scale, transmitted * scale)
+    }
+
+    #[test]
+    fn sampling_matches_scattering() {
+        let mat = RoughDielectric::new(1.5, 0.5);
+        for front_face in [true, false] {
+            let rec = hit(front_face);
+            let r_in = Ray::new(Point3::ZERO, Vec3::new(0.5, 0.0, -0.8) * rec.normal.z);
+            let (reflected, transmitted) = sampled(&mat, &r_in, &rec);
+            let (expected_reflected, expected_transmitted) = integrated(&mat, &r_in, &rec);
+            assert!((reflected - expected_reflected).abs() < 0.01);
+            assert!((transmitted / expected_transmitted - 1.0).abs() < 0.03);
+        }
+    }
+
+    #[test]
+    fn scatters_no_more_light_than_arrives() {
+        for roughness in [0.1, 0.5, 1.0] {
+            let mat = RoughDielectric::new(1.5, roughness);
+            for front_face in [true, false] {
+                let rec = hit(front_face);
+                let r_in = Ray::new(Point3::ZERO, Vec3::new(0.3, 0.0, -0.9) * rec.normal.z);
+                // Nearly smooth glass loses little light between its facets.
+                let min = if roughness < 0.2 { 0.97 } else { 0.0 };
+                assert_scatters_no_more_light_than_arrives(&mat, &r_in, &rec, min);
+                if roughness < 0.2 {
+                    let (reflected, _) = sampled(&mat, &r_in, &rec);
+                    let expected = fresnel::dielectric(0.9 / 0.3f32.hypot(0.9), mat.eta(&rec));
+                    assert!((reflected - expected).abs() < 0.01);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{
+    hittable::HitRecord,
+    pdf::Pdf,
+    sampler::{Sampler, StratifiedSampler},
+    spectrum::reflectance_colour,
+    Point3, Ray, Vec2, Vec3,
+};
+use std::f32::consts::PI;
+
+use super::{Material, Scatter};
+
+/// A hit on the `z = 0` plane from the given side, with the normal facing the ray.
+pub(crate) fn hit(front_face: bool) -> HitRecord {
+    HitRecord {
+        p: Point3::ZERO,
+        normal: if front_face { Vec3::Z } else { -Vec3::Z },
+        front_face,
+        ..Default::default()
+    }
+}
+
+/// A grid of `n * n` stratified samples in `[0, 1)²`.
+pub(crate) fn grid(n: u32) -> impl Iterator<Item = Vec2> {
+    (0..n * n).map(move |k| {
+        Vec2::new(
+            ((k % n) as f32 + 0.5) / n as f32,
+            ((k / n) as f32 + 0.5) / n as f32,
+        )
+    })
+}
+
+/// Maps a uniform sample in `[0, 1)²` to a direction uniformly over the `+z` hemisphere.
+pub(crate) fn uniform_hemisphere(u: Vec2) -> Vec3 {
+    let z = u.x;
+    let r = (1.0 - z * z).sqrt();
+    let phi = 2.0 * PI * u.y;
+    Vec3::new(r * phi.cos(), r * phi.sin(), z)
+}
+
+/// Luminance of the light arriving along `r_in` that `mat` scatters at `rec`, estimated the way
+/// the path integrator does: by scattering, and weighting sampled directions by `eval` over the
+/// density.
+pub(crate) fn scattered(mat: &dyn Material, r_in: &Ray, rec: &HitRecord) -> f32 {
+    let n = 256;
+    let mut sampler = StratifiedSampler::new(n, n);
+    let mut sum = 0.0;
+    for k in 0..n * n {
+        sampler.start_pixel_sample(0, 0, k);
+        match mat.scatter(r_in, rec, &mut sampler) {
+            Some(Scatter::Specular { attenuation, .. }) => sum += attenuation.luminance(),
+            Some(Scatter::SpectralSpecular { reflectance, .. }) => {
+                sum += reflectance_colour(reflectance).luminance()
+            }
+            Some(Scatter::Pdf(pdf)) => sum += sampled(mat, r_in, rec, pdf, &mut sampler),
+            Some(Scatter::WeightedPdf { pdf, weight }) => {
+                sum += weight * sampled(mat, r_in, rec, pdf, &mut sampler)
+            }
+            None => {}
+        }
+    }
+    sum / (n * n) as f32
+}
+
+/// Luminance of the light scattered in a direction sampled from `pdf`, weighted by `eval` over
+/// the density.
+fn sampled(
+    mat: &dyn Material,
+    r_in: &Ray,
+    rec: &HitRecord,
+    pdf: Box<dyn Pdf>,
+    sampler: &mut dyn Sampler,
+) -> f32 {
+    let direction = pdf.generate(sampler.get_2d());
+    let pdf_value = pdf.value(direction);
+    if pdf_value <= 0.0 {
+        return 0.0;
+    }
+    let scattered = Ray::new(rec.p, direction);
+    mat.eval(r_in, rec, &scattered).luminance() / pdf_value
+}
+
+/// Checks that a white `mat` scatters no more of the light arriving along `r_in` than arrives,
+/// and at least `min` of it.
+pub(crate) fn assert_scatters_no_more_light_than_arrives(
+    mat: &dyn Material,
+    r_in: &Ray,
+    rec: &HitRecord,
+    min: f32,
+) {
+    let total = scattered(mat, r_in, rec);
+    assert!(total <= 1.01, "{total}");
+    assert!(total >= min, "{total} below {min}");
+}
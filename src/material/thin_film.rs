@@ -0,0 +1,270 @@
+use crate::{spectrum::reflectance_colour, Colour};
+use std::{
+    f32::consts::PI,
+    ops::{Add, Div, Mul, Sub},
+};
+
+/// A thin transparent coating, such as a soap film, a layer of oil or the anti-reflection
+/// coating of a lens, applied over a [`Metal`](super::Metal), a
+/// [`Conductor`](super::Conductor) or a [`Dielectric`](super::Dielectric).
+///
+/// Light reflected by the top of the film interferes with light reflected by the surface below
+/// it, which a metal also shifts in phase. Depending on the wavelength and on how far the light
+/// travels through the film, which changes with the viewing angle, the reflections add up or
+/// cancel out, giving the shifting colours of soap bubbles and oil slicks.
+///
+/// Light carried as RGB colours is reflected with the colour the film reflects white light as.
+/// A spectral integrator, such as
+/// [`PathIntegrator::spectral`](crate::integrator::PathIntegrator::spectral), finds the
+/// reflection of each wavelength separately.
+///
+/// # Example
+/// ```
+/// use ray_tracing::{
+///     material::{Conductor, Dielectric, Metal, ThinFilm},
+///     Colour,
+/// };
+///
+/// // A soap bubble: a film of soapy water with air on both sides.
+/// let bubble = Dielectric::new(1.0).thin_film(ThinFilm::new(400.0, 1.33));
+/// // Magnesium fluoride reducing the reflections of a lens.
+/// let lens = Dielectric::bk7().thin_film(ThinFilm::new(100.0, 1.38));
+/// // Oil on a dark metal.
+/// let dark_steel = Metal::new(&Colour::new(0.3, 0.3, 0.3), 0.0);
+/// let oily = dark_steel.thin_film(ThinFilm::new(350.0, 1.45));
+/// // Copper tarnished by a thin layer of oxide.
+/// let tarnished = Conductor::copper(0.0).thin_film(ThinFilm::new(150.0, 2.6));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThinFilm {
+    /// Thickness of the film in nanometres.
+    thickness: f32,
+    /// Refractive index of the film.
+    refraction_index: f32,
+}
+
+impl ThinFilm {
+    /// Creates a new thin film coating.
+    ///
+    /// # Parameters
+    /// - `thickness`: The thickness of the film in nanometres. Films from about 100nm to 1000nm
+    ///   show colours; thicker films give colours too fine to see.
+    /// - `refraction_index`: The refractive index of the film, such as 1.33 for soapy water.
+    ///
+    /// # Returns
+    /// A new `ThinFilm`.
+    pub fn new(thickness: f32, refraction_index: f32) -> Self {
+        Self {
+            thickness,
+            refraction_index,
+        }
+    }
+
+    /// Fraction of unpolarised light of wavelength `lambda`, in nanometres, reflected by the film
+    /// and the surface below it, whose complex refractive index is `eta + i k`. Dielectrics have
+    /// no extinction coefficient `k`. The light arrives from air at an angle with cosine
+    /// `cos_theta` to the normal.
+    pub(super) fn reflectance(&self, cos_theta: f32, lambda: f32, eta: f32, k: f32) -> f32 {
+        let n = self.refraction_index;
+        let cos_i = cos_theta.clamp(0.0, 1.0);
+        let sin2_i = 1.0 - cos_i * cos_i;
+        let sin2_film = sin2_i / (n * n);
+        if sin2_film >= 1.0 {
+            // Total internal reflection at the top of the film.
+            return 1.0;
+        }
+        let cos_film = (1.0 - sin2_film).sqrt();
+        // The cosine in the surface below is complex when it absorbs light, or when light is
+        // totally reflected by it.
+        let substrate = Complex::new(eta, k);
+        let cos_substrate =
+            (Complex::real(1.0) - Complex::real(sin2_i) / (substrate * substrate)).sqrt();
+
+        // The extra phase of light making a round trip through the film.
+        let phase = 4.0 * PI * n * self.thickness * cos_film / lambda;
+        let round_trip = Complex::new(phase.cos(), phase.sin());
+        // Sum the light reflected after any number of round trips, following Airy, for each
+        // polarisation from the amplitudes reflected at the top and the bottom of the film. A
+        // metal below the film shifts the phase of the light it reflects.
+        let airy = |top: Complex, bottom: Complex| {
+            let bottom = bottom * round_trip;
+            (top + bottom).norm_sqr() / (Complex::real(1.0) + top * bottom).norm_sqr()
+        };
+        let amplitude = |n1: Complex, cos1: Complex, n2: Complex, cos2: Complex| {
+            (
+                (n1 * cos1 - n2 * cos2) / (n1 * cos1 + n2 * cos2),
+                (n2 * cos1 - n1 * cos2) / (n2 * cos1 + n1 * cos2),
+            )
+        };
+        let film = Complex::real(n);
+        let cos_film = Complex::real(cos_film);
+        let (top_s, top_p) = amplitude(Complex::real(1.0), Complex::real(cos_i), film, cos_film);
+        let (bottom_s, bottom_p) = amplitude(film, cos_film, substrate, cos_substrate);
+        (0.5 * (airy(top_s, bottom_s) + airy(top_p, bottom_p))).min(1.0)
+    }
+
+    /// The colour the film reflects white light as, over a surface whose complex refractive
+    /// index at each wavelength `λ` is `eta + i k`, with `(eta, k) = substrate(λ)`.
+    pub(super) fn reflectance_colour(
+        &self,
+        cos_theta: f32,
+        substrate: impl Fn(f32) -> (f32, f32),
+    ) -> Colour {
+        reflectance_colour(|lambda| {
+            let (eta, k) = substrate(lambda);
+            self.reflectance(cos_theta, lambda, eta, k)
+        })
+    }
+}
+
+/// A complex number, for the amplitudes of light waves reflected by the film.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Complex {
+    re: f32,
+    im: f32,
+}
+
+impl Complex {
+    fn new(re: f32, im: f32) -> Self {
+        Self { re, im }
+    }
+
+    fn real(re: f32) -> Self {
+        Self::new(re, 0.0)
+    }
+
+    /// The square of the magnitude.
+    fn norm_sqr(self) -> f32 {
+        self.re * self.re + self.im * self.im
+    }
+
+    /// The square root with a positive real part.
+    fn sqrt(self) -> Self {
+        let norm = self.norm_sqr().sqrt();
+        let re = (0.5 * (norm + self.re)).max(0.0).sqrt();
+        let im = (0.5 * (norm - self.re)).max(0.0).sqrt();
+        Self::new(re, if self.im < 0.0 { -im } else { im })
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+impl Div for Complex {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        let norm_sqr = rhs.norm_sqr();
+        Self::new(
+            (self.re * rhs.re + self.im * rhs.im) / norm_sqr,
+            (self.im * rhs.re - self.re * rhs.im) / norm_sqr,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::fresnel;
+
+    #[test]
+    fn vanishing_films_reflect_like_the_surface() {
+        let film = ThinFilm::new(0.0, 1.33);
+        for cos_theta in [1.0, 0.7, 0.2] {
+            for substrate in [1.5, 2.4] {
+                let expected = fresnel::dielectric(cos_theta, substrate);
+                let value = film.reflectance(cos_theta, 550.0, substrate, 0.0);
+                assert!((value - expected).abs() < 1e-5, "{value} {expected}");
+            }
+        }
+    }
+
+    #[test]
+    fn vanishing_films_reflect_like_metals() {
+        let film = ThinFilm::new(0.0, 1.45);
+        // Gold in green light.
+        let (eta, k) = (0.375, 2.386);
+        for cos_theta in [1.0, 0.7, 0.2] {
+            let expected = fresnel::conductor(cos_theta, eta, k);
+            let value = film.reflectance(cos_theta, 550.0, eta, k);
+            assert!((value - expected).abs() < 1e-4, "{value} {expected}");
+        }
+    }
+
+    #[test]
+    fn metals_shift_the_phase_of_reflections() {
+        // A dielectric reflecting as much as gold head on, which reflects without a phase shift.
+        let (eta, k) = (0.375, 2.386);
+        let r = fresnel::conductor(1.0, eta, k).sqrt();
+        let dielectric = (1.0 + r) / (1.0 - r);
+        let film = ThinFilm::new(200.0, 1.45);
+        // The phase shift moves the wavelength reflected the most.
+        let brightest = |eta, k| {
+            (400..=700)
+                .step_by(5)
+                .map(|lambda| lambda as f32)
+                .max_by(|&a, &b| {
+                    let r = |lambda| film.reflectance(1.0, lambda, eta, k);
+                    r(a).total_cmp(&r(b))
+                })
+                .unwrap()
+        };
+        let (metal, dielectric) = (brightest(eta, k), brightest(dielectric, 0.0));
+        assert!((metal - dielectric).abs() > 50.0, "{metal} {dielectric}");
+    }
+
+    #[test]
+    fn quarter_wave_coatings_reduce_reflections() {
+        // A quarter-wave magnesium fluoride layer for green light on glass.
+        let film = ThinFilm::new(550.0 / (4.0 * 1.38), 1.38);
+        let uncoated = fresnel::dielectric(1.0, 1.5);
+        assert!(film.reflectance(1.0, 550.0, 1.5, 0.0) < 0.4 * uncoated);
+        // Red and blue light are reflected more than green, giving coated lenses a purple tint.
+        let colour = film.reflectance_colour(1.0, |_| (1.5, 0.0));
+        assert!(
+            colour.r() > colour.g() && colour.b() > colour.g(),
+            "{colour}"
+        );
+        assert!(colour.max_component() < uncoated);
+    }
+
+    #[test]
+    fn colours_change_with_viewing_angle() {
+        let bubble = ThinFilm::new(400.0, 1.33);
+        let head_on = bubble.reflectance_colour(1.0, |_| (1.0, 0.0));
+        let oblique = bubble.reflectance_colour(0.5, |_| (1.0, 0.0));
+        let d = head_on - oblique;
+        assert!(d.r().abs().max(d.g().abs()).max(d.b().abs()) > 0.02);
+        // Films reflect light between none and all of it at every wavelength.
+        for lambda in [400.0, 500.0, 600.0, 700.0] {
+            for cos_theta in [1.0, 0.5, 0.1] {
+                let r = bubble.reflectance(cos_theta, lambda, 1.0, 0.0);
+                assert!((0.0..=1.0).contains(&r));
+            }
+        }
+    }
+}
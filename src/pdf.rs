@@ -0,0 +1,88 @@
+use crate::{Vec2, Vec3};
+
+mod cosine;
+mod hittable;
+mod mixture;
+mod sphere;
+
+pub use cosine::CosinePdf;
+pub use hittable::HittablePdf;
+pub use mixture::MixturePdf;
+pub use sphere::SpherePdf;
+
+/// A probability density over directions, used to importance sample scattered rays.
+///
+/// Tracing rays in the directions light is most likely to come from, and dividing each ray's
+/// contribution by the density it was sampled with, gives the same average image with much less
+/// noise than sampling directions uniformly.
+pub trait Pdf {
+    /// The density of sampling `direction`, with respect to solid angle.
+    fn value(&self, direction: Vec3) -> f32;
+
+    /// Maps a uniform sample in `[0, 1)²` to a direction distributed according to the density.
+    fn generate(&self, u: Vec2) -> Vec3;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hittable::HittableList,
+        material::{test_util::grid, Lambertian},
+        sampler::sample_unit_vector,
+        Colour, Point3, Sphere,
+    };
+    use std::{f32::consts::PI, rc::Rc};
+
+    /// Estimates the integral of the density over the sphere of directions.
+    fn integral(pdf: &dyn Pdf) -> f32 {
+        let samples: Vec<Vec2> = grid(256).collect();
+        let sum: f32 = samples
+            .iter()
+            .map(|&u| pdf.value(sample_unit_vector(u)))
+            .sum();
+        sum * 4.0 * PI / samples.len() as f32
+    }
+
+    /// Estimates the solid angle the density is non-zero over from directions it generates.
+    fn support(pdf: &dyn Pdf) -> f32 {
+        let samples: Vec<Vec2> = grid(256).collect();
+        let sum: f32 = samples
+            .iter()
+            .map(|&u| 1.0 / pdf.value(pdf.generate(u)))
+            .sum();
+        sum / samples.len() as f32
+    }
+
+    fn lights() -> HittableList {
+        crate::hittable_list![Rc::new(Sphere::new(
+            Point3::new(0.0, 4.0, 0.0),
+            1.0,
+            Rc::new(Lambertian::new(&Colour::new(1.0, 1.0, 1.0))),
+        ))]
+    }
+
+    #[test]
+    fn pdfs_integrate_to_one() {
+        let lights = lights();
+        let cosine = CosinePdf::new(Vec3::new(0.0, 1.0, 1.0));
+        let sphere = SpherePdf;
+        let towards = HittablePdf::new(&lights, Point3::ZERO);
+        let mixture = MixturePdf::new(&cosine, &towards);
+        for pdf in [&cosine as &dyn Pdf, &sphere, &towards, &mixture] {
+            assert!((integral(pdf) - 1.0).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn pdfs_generate_directions_they_cover() {
+        let lights = lights();
+        let cosine = CosinePdf::new(Vec3::Z);
+        let towards = HittablePdf::new(&lights, Point3::ZERO);
+        assert!(grid(64).all(|u| cosine.generate(u).z >= 0.0));
+        assert!((support(&SpherePdf) / (4.0 * PI) - 1.0).abs() < 1e-3);
+        // Solid angle of a unit sphere seen from four units away.
+        let cone = 2.0 * PI * (1.0 - (1.0 - 1.0 / 16.0_f32).sqrt());
+        assert!((support(&towards) / cone - 1.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,30 @@
+use crate::{sampler::sample_cosine_hemisphere, util::Onb, Vec2, Vec3};
+use std::f32::consts::PI;
+
+use super::Pdf;
+
+/// A density proportional to the cosine of the angle to a surface normal, over the hemisphere
+/// around the normal. This matches the light scattered by an ideal diffuse surface.
+#[derive(Debug, Clone, Copy)]
+pub struct CosinePdf {
+    /// Basis with the normal as its `w` axis.
+    uvw: Onb,
+}
+
+impl CosinePdf {
+    /// Creates a new cosine density around the normal `w`.
+    pub fn new(w: Vec3) -> Self {
+        Self { uvw: Onb::new(w) }
+    }
+}
+
+impl Pdf for CosinePdf {
+    fn value(&self, direction: Vec3) -> f32 {
+        let cosine_theta = direction.normalize().dot(self.uvw.w());
+        f32::max(0.0, cosine_theta / PI)
+    }
+
+    fn generate(&self, u: Vec2) -> Vec3 {
+        self.uvw.transform(sample_cosine_hemisphere(u))
+    }
+}
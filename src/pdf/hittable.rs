@@ -0,0 +1,31 @@
+use crate::{hittable::Hittable, Point3, Vec2, Vec3};
+
+use super::Pdf;
+
+/// A density over the directions from a point towards a hittable, such as the lights of a scene.
+///
+/// Uses [`Hittable::random`] and [`Hittable::pdf_value`], so the density is zero everywhere for
+/// hittables that do not support sampling.
+pub struct HittablePdf<'a> {
+    /// The hittable to sample directions towards.
+    objects: &'a dyn Hittable,
+    /// The point directions start from.
+    origin: Point3,
+}
+
+impl<'a> HittablePdf<'a> {
+    /// Creates a new density over the directions from `origin` towards `objects`.
+    pub fn new(objects: &'a dyn Hittable, origin: Point3) -> Self {
+        Self { objects, origin }
+    }
+}
+
+impl Pdf for HittablePdf<'_> {
+    fn value(&self, direction: Vec3) -> f32 {
+        self.objects.pdf_value(self.origin, direction)
+    }
+
+    fn generate(&self, u: Vec2) -> Vec3 {
+        self.objects.random(self.origin, u)
+    }
+}
@@ -0,0 +1,37 @@
+use crate::{Vec2, Vec3};
+
+use super::Pdf;
+
+/// An equal mixture of two densities, which samples either one with the same probability.
+///
+/// Mixing a material's density with a density towards the lights samples both the directions the
+/// material scatters light in and the directions light comes from.
+pub struct MixturePdf<'a> {
+    /// The densities being mixed.
+    pdfs: [&'a dyn Pdf; 2],
+}
+
+impl<'a> MixturePdf<'a> {
+    /// Creates a new equal mixture of `first` and `second`.
+    pub fn new(first: &'a dyn Pdf, second: &'a dyn Pdf) -> Self {
+        Self {
+            pdfs: [first, second],
+        }
+    }
+}
+
+impl Pdf for MixturePdf<'_> {
+    fn value(&self, direction: Vec3) -> f32 {
+        0.5 * self.pdfs[0].value(direction) + 0.5 * self.pdfs[1].value(direction)
+    }
+
+    fn generate(&self, u: Vec2) -> Vec3 {
+        // Choose a density with the first dimension and stretch what is left of it back over
+        // [0, 1), so the chosen density still gets a well distributed sample.
+        if u.x < 0.5 {
+            self.pdfs[0].generate(Vec2::new(2.0 * u.x, u.y))
+        } else {
+            self.pdfs[1].generate(Vec2::new(2.0 * u.x - 1.0, u.y))
+        }
+    }
+}
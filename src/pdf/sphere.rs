@@ -0,0 +1,18 @@
+use crate::{sampler::sample_unit_vector, Vec2, Vec3};
+use std::f32::consts::PI;
+
+use super::Pdf;
+
+/// A uniform density over every direction.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SpherePdf;
+
+impl Pdf for SpherePdf {
+    fn value(&self, _direction: Vec3) -> f32 {
+        1.0 / (4.0 * PI)
+    }
+
+    fn generate(&self, u: Vec2) -> Vec3 {
+        sample_unit_vector(u)
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{
+    hittable::{HitRecord, Hittable},
+    interval::Interval,
+    material::Material,
+    Point3, Ray, Vec2, Vec3,
+};
+use std::rc::Rc;
+
+/// A parallelogram in 3D space, defined by a corner and the two edges leaving it.
+pub struct Quad {
+    /// Corner of the quad.
+    q: Point3,
+    /// First edge from the corner.
+    u: Vec3,
+    /// Second edge from the corner.
+    v: Vec3,
+    /// The material the quad is made of.
+    mat: Rc<dyn Material>,
+    /// Unit normal of the quad's plane, in the direction of `u × v`.
+    normal: Vec3,
+    /// Offset of the plane from the origin along the normal.
+    d: f32,
+    /// Vector used to find the planar coordinates of a point on the plane.
+    w: Vec3,
+    /// Area of the quad.
+    area: f32,
+}
+
+impl Quad {
+    /// Creates a new quad with the given corner, edges, and material.
+    ///
+    /// # Parameters
+    /// - `q`: A corner of the quad.
+    /// - `u`: The first edge leaving the corner.
+    /// - `v`: The second edge leaving the corner. The quad's front face is the side `u × v`
+    ///   points towards.
+    /// - `mat`: The material of the quad.
+    ///
+    /// # Returns
+    /// A new `Quad` instance.
+    pub fn new(q: Point3, u: Vec3, v: Vec3, mat: Rc<dyn Material>) -> Self {
+        let n = u.cross(v);
+        let normal = n.normalize();
+        Self {
+            q,
+            u,
+            v,
+            mat,
+            normal,
+            d: normal.dot(q),
+            w: n / n.dot(n),
+            area: n.length(),
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
+        let denom = self.normal.dot(*r.direction());
+
+        // No hit if the ray is parallel to the plane.
+        if denom.abs() < 1e-8 {
+            return false;
+        }
+
+        // No hit if the hit point parameter t is outside the ray interval.
+        let t = (self.d - self.normal.dot(*r.origin())) / denom;
+        if !ray_t.contains(t) {
+            return false;
+        }
+
+        // No hit if the hit point lies outside the quad, using its planar coordinates.
+        let intersection = r.at(t);
+        let planar_hitpt_vector = intersection - self.q;
+        let alpha = self.w.dot(planar_hitpt_vector.cross(self.v));
+        let beta = self.w.dot(self.u.cross(planar_hitpt_vector));
+        let unit_interval = Interval::new(0.0, 1.0);
+        if !unit_interval.contains(alpha) || !unit_interval.contains(beta) {
+            return false;
+        }
+
+        rec.t = t;
+        rec.p = intersection;
+        rec.set_face_normal(r, &self.normal);
+
+        true
+    }
+
+    fn mat(&self) -> Option<Rc<dyn Material>> {
+        Some(self.mat.clone())
+    }
+
+    /// The density of sampling `direction` by choosing a point uniformly over the quad's area.
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f32 {
+        let mut rec = HitRecord::default();
+        if !self.hit(
+            &Ray::new(origin, direction),
+            Interval::new(0.001, f32::INFINITY),
+            &mut rec,
+        ) {
+            return 0.0;
+        }
+
+        // Convert the density over the area to a density over solid angle.
+        let distance_squared = rec.t * rec.t * direction.length_squared();
+        let cosine = (direction.dot(self.normal) / direction.length()).abs();
+        distance_squared / (cosine * self.area)
+    }
+
+    /// Samples a direction towards a point chosen uniformly over the quad's area.
+    fn random(&self, origin: Point3, u: Vec2) -> Vec3 {
+        let p = self.q + (u.x * self.u) + (u.y * self.v);
+        p - origin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::Lambertian, Colour};
+
+    fn quad() -> Quad {
+        Quad::new(
+            Point3::new(-1.0, -1.0, -2.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            Rc::new(Lambertian::new(&Colour::new(0.5, 0.5, 0.5))),
+        )
+    }
+
+    #[test]
+    fn quad_hit_inside_and_outside() {
+        let quad = quad();
+        let mut rec = HitRecord::default();
+        let ray_t = Interval::new(0.001, f32::INFINITY);
+        assert!(quad.hit(&Ray::new(Point3::ZERO, -Vec3::Z), ray_t, &mut rec));
+        assert_eq!(rec.t, 2.0);
+        assert!(rec.front_face);
+        assert!(!quad.hit(
+            &Ray::new(Point3::ZERO, Vec3::new(1.0, 0.0, -1.0)),
+            ray_t,
+            &mut rec
+        ));
+    }
+
+    #[test]
+    fn quad_random_directions_hit_quad() {
+        let quad = quad();
+        for u in [
+            Vec2::new(0.1, 0.2),
+            Vec2::new(0.5, 0.5),
+            Vec2::new(0.9, 0.7),
+        ] {
+            let direction = quad.random(Point3::ZERO, u);
+            assert!(quad.pdf_value(Point3::ZERO, direction) > 0.0);
+        }
+        assert_eq!(quad.pdf_value(Point3::ZERO, Vec3::Z), 0.0);
+    }
+}
@@ -7,6 +7,8 @@ pub struct Ray {
     orig: Point3,
     /// The direction vector of the ray.
     dir: Vec3,
+    /// Wavelength of the light carried by the ray in nanometres, when rendering spectrally.
+    wavelength: Option<f32>,
 }
 
 impl Ray {
@@ -29,9 +31,33 @@ impl Ray {
         Self {
             orig: origin,
             dir: direction,
+            wavelength: None,
         }
     }
 
+    /// Returns the ray carrying light of the given wavelength, in nanometres, so that materials
+    /// whose behaviour depends on the wavelength can take it into account.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{Ray, Point3, Vec3};
+    /// let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 1.0, 0.0));
+    /// assert_eq!(ray.wavelength(), None);
+    /// assert_eq!(ray.with_wavelength(550.0).wavelength(), Some(550.0));
+    /// ```
+    pub fn with_wavelength(self, wavelength: f32) -> Self {
+        Self {
+            wavelength: Some(wavelength),
+            ..self
+        }
+    }
+
+    /// Returns the wavelength of the light carried by the ray in nanometres, or `None` if the
+    /// ray carries RGB light.
+    pub fn wavelength(&self) -> Option<f32> {
+        self.wavelength
+    }
+
     /// Returns a reference to the origin point of the ray.
     ///
     /// # Returns
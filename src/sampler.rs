@@ -0,0 +1,277 @@
+use crate::{Vec2, Vec3};
+use std::f32::consts::PI;
+
+mod blue_noise;
+mod halton;
+mod independent;
+mod sobol;
+mod stratified;
+
+pub use blue_noise::BlueNoiseSampler;
+pub use halton::HaltonSampler;
+pub use independent::IndependentSampler;
+pub use sobol::SobolSampler;
+pub use stratified::StratifiedSampler;
+
+/// Generates the sample values used to trace a path through a pixel.
+///
+/// Each camera sample starts with a call to [`Sampler::start_pixel_sample`], after which every
+/// call to [`Sampler::get_1d`] or [`Sampler::get_2d`] returns the next dimension(s) of the sample
+/// in `[0, 1)`. The camera takes the first two dimensions for the position within the pixel and
+/// the next two for the position on the lens, and materials take the following dimensions at
+/// each bounce. Samplers that distribute the samples of a pixel more evenly than independent
+/// random numbers make the image converge with fewer samples.
+pub trait Sampler: std::fmt::Debug {
+    /// Starts generating the dimensions of sample `sample_index` of the pixel at column `i` and
+    /// row `j`.
+    fn start_pixel_sample(&mut self, i: u32, j: u32, sample_index: u32);
+
+    /// Returns the next dimension of the current sample.
+    fn get_1d(&mut self) -> f32;
+
+    /// Returns the next two dimensions of the current sample.
+    fn get_2d(&mut self) -> Vec2;
+
+    /// Clones the sampler into a new box, so that each render can own its own sampler state.
+    fn clone_box(&self) -> Box<dyn Sampler>;
+}
+
+impl Clone for Box<dyn Sampler> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Maps a uniform sample in `[0, 1)²` to a point on the unit sphere, uniformly distributed by area.
+///
+/// # Example
+/// ```
+/// use ray_tracing::{sampler::sample_unit_vector, Vec2};
+/// let v = sample_unit_vector(Vec2::new(0.3, 0.8));
+/// assert!((v.length() - 1.0).abs() < 1e-6);
+/// ```
+pub fn sample_unit_vector(u: Vec2) -> Vec3 {
+    let z = 1.0 - 2.0 * u.x;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let phi = 2.0 * PI * u.y;
+    Vec3::new(r * phi.cos(), r * phi.sin(), z)
+}
+
+/// Maps a uniform sample in `[0, 1)²` to a point in the unit disk in the xy plane, using the
+/// concentric mapping so that stratified samples stay stratified on the disk.
+///
+/// # Example
+/// ```
+/// use ray_tracing::{sampler::sample_unit_disk, Vec2, Vec3};
+/// assert_eq!(sample_unit_disk(Vec2::new(0.5, 0.5)), Vec3::ZERO);
+/// assert!(sample_unit_disk(Vec2::new(0.99, 0.1)).length() < 1.0);
+/// ```
+pub fn sample_unit_disk(u: Vec2) -> Vec3 {
+    let offset = 2.0 * u - Vec2::ONE;
+    if offset == Vec2::ZERO {
+        return Vec3::ZERO;
+    }
+    let (r, theta) = if offset.x.abs() > offset.y.abs() {
+        (offset.x, PI / 4.0 * (offset.y / offset.x))
+    } else {
+        (offset.y, PI / 2.0 - PI / 4.0 * (offset.x / offset.y))
+    };
+    Vec3::new(r * theta.cos(), r * theta.sin(), 0.0)
+}
+
+/// Maps a uniform sample in `[0, 1)²` to a direction in the hemisphere around the z axis, with a
+/// density proportional to the cosine of the angle to the z axis.
+///
+/// # Example
+/// ```
+/// use ray_tracing::{sampler::sample_cosine_hemisphere, Vec2};
+/// let v = sample_cosine_hemisphere(Vec2::new(0.3, 0.8));
+/// assert!((v.length() - 1.0).abs() < 1e-6);
+/// assert!(v.z >= 0.0);
+/// ```
+pub fn sample_cosine_hemisphere(u: Vec2) -> Vec3 {
+    // Malley's method: project points uniformly distributed on the disk up onto the hemisphere.
+    let d = sample_unit_disk(u);
+    let z = (1.0 - d.x * d.x - d.y * d.y).max(0.0).sqrt();
+    Vec3::new(d.x, d.y, z)
+}
+
+/// The largest `f32` below one, used to keep sample values in `[0, 1)`.
+const ONE_MINUS_EPSILON: f32 = 1.0 - f32::EPSILON / 2.0;
+
+/// Position of a sampler within the sample it is generating.
+#[derive(Debug, Default, Clone, Copy)]
+struct PixelSample {
+    /// Pixel column.
+    i: u32,
+    /// Pixel row.
+    j: u32,
+    /// Index of the sample within the pixel.
+    index: u32,
+    /// Next dimension of the sample to generate.
+    dimension: u32,
+}
+
+impl PixelSample {
+    fn start(&mut self, i: u32, j: u32, index: u32) {
+        *self = Self {
+            i,
+            j,
+            index,
+            dimension: 0,
+        };
+    }
+
+    /// Returns the current dimension and moves on to the next `count` dimensions.
+    fn advance(&mut self, count: u32) -> u32 {
+        let dimension = self.dimension;
+        self.dimension += count;
+        dimension
+    }
+}
+
+/// Scrambles the bits of a 64-bit value so that similar inputs give unrelated outputs.
+fn mix_bits(mut v: u64) -> u64 {
+    v ^= v >> 31;
+    v = v.wrapping_mul(0x7fb5_d329_728e_a185);
+    v ^= v >> 27;
+    v = v.wrapping_mul(0x81da_def4_bc2d_d44d);
+    v ^= v >> 33;
+    v
+}
+
+/// Hashes a list of values into a 64-bit value.
+fn hash(values: &[u32]) -> u64 {
+    values.iter().fold(0x9e37_79b9_7f4a_7c15, |h, &v| {
+        mix_bits(h ^ (v as u64).wrapping_add(0x632b_e59b_d9b4_e019))
+    })
+}
+
+/// Converts 32 random bits to a value in `[0, 1)`.
+fn bits_to_unit(bits: u32) -> f32 {
+    (bits as f32 * (1.0 / 4_294_967_296.0)).min(ONE_MINUS_EPSILON)
+}
+
+/// Returns element `i` of a random permutation of `0..n` chosen by `seed`, without building the
+/// permutation. This is Kensler's hash-based permutation from "Correlated Multi-Jittered Sampling".
+fn permutation_element(mut i: u32, n: u32, seed: u32) -> u32 {
+    let mut w = n.wrapping_sub(1);
+    w |= w >> 1;
+    w |= w >> 2;
+    w |= w >> 4;
+    w |= w >> 8;
+    w |= w >> 16;
+    loop {
+        i ^= seed;
+        i = i.wrapping_mul(0xe170_893d);
+        i ^= seed >> 16;
+        i ^= (i & w) >> 4;
+        i ^= seed >> 8;
+        i = i.wrapping_mul(0x0929_eb3f);
+        i ^= seed >> 23;
+        i ^= (i & w) >> 1;
+        i = i.wrapping_mul(1 | seed >> 27);
+        i = i.wrapping_mul(0x6935_fa69);
+        i ^= (i & w) >> 11;
+        i = i.wrapping_mul(0x74dc_b303);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0x9e50_1cc3);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0xc860_a3df);
+        i &= w;
+        i ^= i >> 5;
+        if i < n {
+            break;
+        }
+    }
+    ((u64::from(i) + u64::from(seed)) % u64::from(n)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permutation_element_is_a_permutation() {
+        for n in [1, 2, 7, 16, 100] {
+            let mut seen = vec![false; n as usize];
+            for i in 0..n {
+                let p = permutation_element(i, n, 0xdead_beef);
+                assert!(!seen[p as usize]);
+                seen[p as usize] = true;
+            }
+        }
+    }
+
+    #[test]
+    fn permutation_element_handles_seeds_near_the_top_of_the_range() {
+        for seed in [u32::MAX - 5, u32::MAX - 1, u32::MAX] {
+            let mut seen = [false; 7];
+            for i in 0..7 {
+                let p = permutation_element(i, 7, seed);
+                assert!(!seen[p as usize]);
+                seen[p as usize] = true;
+            }
+        }
+    }
+
+    #[test]
+    fn unit_disk_samples_stay_in_disk() {
+        for x in 0..16 {
+            for y in 0..16 {
+                let u = Vec2::new(x as f32 / 16.0, y as f32 / 16.0);
+                assert!(sample_unit_disk(u).length() <= 1.0 + 1e-6);
+            }
+        }
+    }
+
+    /// Checks that the sampler produces values in `[0, 1)` and that the first dimension of `n`
+    /// samples puts no more than `max_per_cell` samples in each of `n` equal cells.
+    fn check_sampler(mut sampler: impl Sampler, n: u32, max_per_cell: u32) {
+        let mut cells = vec![0; n as usize];
+        for index in 0..n {
+            sampler.start_pixel_sample(3, 5, index);
+            let x = sampler.get_1d();
+            assert!((0.0..1.0).contains(&x));
+            let u = sampler.get_2d();
+            assert!((0.0..1.0).contains(&u.x) && (0.0..1.0).contains(&u.y));
+            for _ in 0..40 {
+                let v = sampler.get_1d();
+                assert!((0.0..1.0).contains(&v));
+            }
+            cells[(x * n as f32) as usize] += 1;
+        }
+        assert!(cells.iter().all(|&c| c <= max_per_cell));
+    }
+
+    #[test]
+    fn stratified_sampler_covers_strata() {
+        check_sampler(StratifiedSampler::new(4, 4).seed(7), 16, 1);
+    }
+
+    #[test]
+    fn halton_sampler_covers_strata() {
+        check_sampler(HaltonSampler::new(7), 16, 1);
+    }
+
+    #[test]
+    fn sobol_sampler_covers_strata() {
+        check_sampler(SobolSampler::new(7), 16, 1);
+    }
+
+    #[test]
+    fn blue_noise_sampler_covers_strata() {
+        // Rotating the samples by the blue noise offset can move up to two into the same cell.
+        check_sampler(BlueNoiseSampler::new(7), 16, 2);
+    }
+
+    #[test]
+    fn samplers_are_deterministic() {
+        let mut a = SobolSampler::new(1);
+        let mut b = a.clone_box();
+        a.start_pixel_sample(1, 2, 3);
+        b.start_pixel_sample(1, 2, 3);
+        assert_eq!(a.get_2d(), b.get_2d());
+        assert_eq!(a.get_1d(), b.get_1d());
+    }
+}
@@ -0,0 +1,80 @@
+use crate::Vec2;
+
+use super::{
+    bits_to_unit, hash, mix_bits, permutation_element, PixelSample, Sampler, ONE_MINUS_EPSILON,
+};
+
+/// Prime bases for the dimensions of the Halton sequence.
+const PRIMES: [u32; 32] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131,
+];
+
+/// A sampler using the low-discrepancy Halton sequence.
+///
+/// Dimension `d` of sample `n` is the radical inverse of `n` in the `d`-th prime base, with the
+/// digits randomly permuted for every pixel so neighbouring pixels do not share the same pattern.
+/// The permutations also break up the correlation between dimensions with similar large bases.
+/// Dimensions beyond the supported prime bases fall back to hashed random values.
+#[derive(Debug, Clone, Copy)]
+pub struct HaltonSampler {
+    /// Seed for the digit scrambling.
+    seed: u32,
+    /// The sample currently being generated.
+    pixel: PixelSample,
+}
+
+impl HaltonSampler {
+    /// Creates a new Halton sampler whose scrambling is chosen by `seed`.
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            pixel: PixelSample::default(),
+        }
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn start_pixel_sample(&mut self, i: u32, j: u32, sample_index: u32) {
+        self.pixel.start(i, j, sample_index);
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let dimension = self.pixel.advance(1);
+        let PixelSample { i, j, index, .. } = self.pixel;
+        let scramble = hash(&[i, j, dimension, self.seed]);
+        match PRIMES.get(dimension as usize) {
+            Some(&base) => scrambled_radical_inverse(base, index, scramble),
+            None => bits_to_unit(hash(&[index, scramble as u32]) as u32),
+        }
+    }
+
+    fn get_2d(&mut self) -> Vec2 {
+        Vec2::new(self.get_1d(), self.get_1d())
+    }
+
+    fn clone_box(&self) -> Box<dyn Sampler> {
+        Box::new(*self)
+    }
+}
+
+/// Mirrors the digits of `index` in `base` about the radix point, passing each digit through a
+/// random permutation chosen by `scramble` and the digit's position.
+fn scrambled_radical_inverse(base: u32, index: u32, scramble: u64) -> f32 {
+    let inv_base = 1.0 / base as f64;
+    let mut inv_base_m = 1.0;
+    let mut remaining = index;
+    let mut result = 0.0;
+    let mut position = 0;
+    // Keep generating digits, including the leading zeros, until they are too small to affect
+    // an f32 value.
+    while inv_base_m > 1e-9 {
+        let digit = remaining % base;
+        remaining /= base;
+        let permutation = mix_bits(scramble ^ position) as u32;
+        inv_base_m *= inv_base;
+        result += permutation_element(digit, base, permutation) as f64 * inv_base_m;
+        position += 1;
+    }
+    (result as f32).min(ONE_MINUS_EPSILON)
+}
@@ -0,0 +1,26 @@
+use crate::{random::random_f32, Vec2};
+
+use super::Sampler;
+
+/// A sampler that returns independent uniform random values for every dimension.
+///
+/// This is the simplest sampler and the camera's default, but it converges slowest as samples
+/// can clump together or leave gaps.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct IndependentSampler;
+
+impl Sampler for IndependentSampler {
+    fn start_pixel_sample(&mut self, _i: u32, _j: u32, _sample_index: u32) {}
+
+    fn get_1d(&mut self) -> f32 {
+        random_f32()
+    }
+
+    fn get_2d(&mut self) -> Vec2 {
+        Vec2::new(random_f32(), random_f32())
+    }
+
+    fn clone_box(&self) -> Box<dyn Sampler> {
+        Box::new(*self)
+    }
+}
@@ -0,0 +1,96 @@
+use crate::Vec2;
+
+use super::{bits_to_unit, hash, PixelSample, Sampler};
+
+/// A sampler using the Owen-scrambled Sobol sequence.
+///
+/// Every call for one or two dimensions takes the first two dimensions of the Sobol sequence,
+/// which together form a (0, 2)-sequence, and scrambles them independently with a nested
+/// uniform (Owen) scramble seeded by the pixel and dimension. The sample index is shuffled in the
+/// same way, so successive dimensions are decorrelated while each keeps the excellent
+/// stratification of the first two Sobol dimensions. This "padded" approach follows Burley,
+/// "Practical Hash-based Owen Scrambling", 2020.
+#[derive(Debug, Clone, Copy)]
+pub struct SobolSampler {
+    /// Seed for the scrambling.
+    seed: u32,
+    /// The sample currently being generated.
+    pixel: PixelSample,
+}
+
+impl SobolSampler {
+    /// Creates a new Sobol sampler whose scrambling is chosen by `seed`.
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            pixel: PixelSample::default(),
+        }
+    }
+
+    /// Hash seeding the scrambles of the given dimension of the current sample.
+    fn scramble(&self, dimension: u32) -> u64 {
+        hash(&[self.pixel.i, self.pixel.j, dimension, self.seed])
+    }
+}
+
+impl Sampler for SobolSampler {
+    fn start_pixel_sample(&mut self, i: u32, j: u32, sample_index: u32) {
+        self.pixel.start(i, j, sample_index);
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let dimension = self.pixel.advance(1);
+        let scramble = self.scramble(dimension);
+        owen_scrambled_sobol_2d(self.pixel.index, scramble).x
+    }
+
+    fn get_2d(&mut self) -> Vec2 {
+        let dimension = self.pixel.advance(2);
+        let scramble = self.scramble(dimension);
+        owen_scrambled_sobol_2d(self.pixel.index, scramble)
+    }
+
+    fn clone_box(&self) -> Box<dyn Sampler> {
+        Box::new(*self)
+    }
+}
+
+/// Returns point `index` of the first two Sobol dimensions, with the index shuffled and both
+/// dimensions scrambled by Owen scrambles seeded from `scramble`.
+pub(super) fn owen_scrambled_sobol_2d(index: u32, scramble: u64) -> Vec2 {
+    let index = nested_uniform_scramble(index, scramble as u32);
+    let x = nested_uniform_scramble(index.reverse_bits(), (scramble >> 32) as u32);
+    let y = nested_uniform_scramble(sobol_dimension_1(index), (scramble >> 16) as u32);
+    Vec2::new(bits_to_unit(x), bits_to_unit(y))
+}
+
+/// The second dimension of the Sobol sequence, whose direction numbers follow the primitive
+/// polynomial `x + 1`.
+fn sobol_dimension_1(mut index: u32) -> u32 {
+    let mut result = 0;
+    let mut v = 1 << 31;
+    while index != 0 {
+        if index & 1 != 0 {
+            result ^= v;
+        }
+        index >>= 1;
+        v ^= v >> 1;
+    }
+    result
+}
+
+/// Applies a nested uniform (Owen) scramble to the bits of `x`, treating it as a binary fraction.
+fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+    laine_karras_permutation(x.reverse_bits(), seed).reverse_bits()
+}
+
+/// A hash in which each bit only depends on the bits below it, which is an Owen scramble when
+/// applied to bit-reversed values.
+fn laine_karras_permutation(mut x: u32, seed: u32) -> u32 {
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50_b47c);
+    x ^= x.wrapping_mul(0xb82f_1e52);
+    x ^= x.wrapping_mul(0xc7af_e638);
+    x ^= x.wrapping_mul(0x8d22_f6e6);
+    x
+}
@@ -0,0 +1,113 @@
+use crate::Vec2;
+
+use super::{bits_to_unit, hash, permutation_element, PixelSample, Sampler, ONE_MINUS_EPSILON};
+
+/// A sampler that divides each dimension into strata and places one jittered sample in each.
+///
+/// One-dimensional values are stratified into `x_strata * y_strata` intervals and two-dimensional
+/// values into an `x_strata` by `y_strata` grid. The strata are visited in a different random
+/// order for every pixel and dimension so that dimensions are not correlated. Once every stratum
+/// of a pixel has been used, further samples start a new, independently shuffled, round.
+#[derive(Debug, Clone, Copy)]
+pub struct StratifiedSampler {
+    /// Number of horizontal strata.
+    x_strata: u32,
+    /// Number of vertical strata.
+    y_strata: u32,
+    /// Seed for the order of the strata and the jitter within them.
+    seed: u32,
+    /// The sample currently being generated.
+    pixel: PixelSample,
+}
+
+impl StratifiedSampler {
+    /// Creates a new stratified sampler with an `x_strata` by `y_strata` grid of strata, so it
+    /// is best used with `x_strata * y_strata` samples per pixel.
+    ///
+    /// # Panics
+    /// Panics if either count of strata is zero.
+    pub fn new(x_strata: u32, y_strata: u32) -> Self {
+        assert!(x_strata > 0 && y_strata > 0);
+        Self {
+            x_strata,
+            y_strata,
+            seed: 0,
+            pixel: PixelSample::default(),
+        }
+    }
+
+    /// Seed choosing the order of the strata and the jitter within them. Defaults to 0.
+    pub fn seed(self, seed: u32) -> Self {
+        Self { seed, ..self }
+    }
+
+    /// Picks the stratum of the current sample for the dimension.
+    fn stratum(&self, dimension: u32) -> u32 {
+        let count = self.x_strata * self.y_strata;
+        let round = self.pixel.index / count;
+        let seed = hash(&[self.pixel.i, self.pixel.j, dimension, round, self.seed]);
+        permutation_element(self.pixel.index % count, count, seed as u32)
+    }
+
+    /// Random offset of the current sample within its stratum along `axis` of the dimension.
+    fn jitter(&self, dimension: u32, axis: u32) -> f32 {
+        let PixelSample { i, j, index, .. } = self.pixel;
+        bits_to_unit(hash(&[i, j, index, dimension, axis, self.seed]) as u32)
+    }
+}
+
+/// The point `jitter` of the way through stratum `stratum` of `count` equal strata of `[0, 1)`.
+fn in_stratum(stratum: u32, count: u32, jitter: f32) -> f32 {
+    // Rounding can reach one at the end of the last stratum.
+    ((stratum as f32 + jitter) / count as f32).min(ONE_MINUS_EPSILON)
+}
+
+impl Sampler for StratifiedSampler {
+    fn start_pixel_sample(&mut self, i: u32, j: u32, sample_index: u32) {
+        self.pixel.start(i, j, sample_index);
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let dimension = self.pixel.advance(1);
+        let count = self.x_strata * self.y_strata;
+        let stratum = self.stratum(dimension);
+        in_stratum(stratum, count, self.jitter(dimension, 0))
+    }
+
+    fn get_2d(&mut self) -> Vec2 {
+        let dimension = self.pixel.advance(2);
+        let stratum = self.stratum(dimension);
+        let x = stratum % self.x_strata;
+        let y = stratum / self.x_strata;
+        Vec2::new(
+            in_stratum(x, self.x_strata, self.jitter(dimension, 0)),
+            in_stratum(y, self.y_strata, self.jitter(dimension, 1)),
+        )
+    }
+
+    fn clone_box(&self) -> Box<dyn Sampler> {
+        Box::new(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_stay_below_one() {
+        assert!(in_stratum(2, 3, ONE_MINUS_EPSILON) < 1.0);
+        assert!(in_stratum(0, 3, 0.0) == 0.0);
+    }
+
+    #[test]
+    fn seeded_samples_are_reproducible() {
+        let values = |seed| {
+            let mut sampler = StratifiedSampler::new(2, 2).seed(seed);
+            sampler.start_pixel_sample(4, 7, 3);
+            (sampler.get_1d(), sampler.get_2d())
+        };
+        assert_eq!(values(1), values(1));
+        assert_ne!(values(1), values(2));
+    }
+}
@@ -0,0 +1,369 @@
+use crate::{Colour, Vec3};
+use glam::Mat3;
+use std::{
+    ops::{Add, AddAssign, Index, Mul},
+    sync::OnceLock,
+};
+
+/// Shortest wavelength sampled, in nanometres.
+pub const LAMBDA_MIN: f32 = 360.0;
+/// Longest wavelength sampled, in nanometres.
+pub const LAMBDA_MAX: f32 = 830.0;
+/// Number of wavelengths carried by each path.
+pub const N_WAVELENGTHS: usize = 4;
+
+/// The wavelengths a path carries light at, with the density each was sampled with.
+///
+/// One hero wavelength is sampled and the others are spread evenly from it, so a path covers
+/// the whole visible range with well stratified wavelengths. Wavelengths are sampled in
+/// proportion to how visible they are, so little time is spent on light the eye barely sees.
+///
+/// # Example
+/// ```
+/// use ray_tracing::{spectrum::{SampledSpectrum, SampledWavelengths}, Colour};
+///
+/// let lambdas = SampledWavelengths::sample_visible(0.3);
+/// let spectrum = SampledSpectrum::from_colour(Colour::new(1.0, 1.0, 1.0), &lambdas);
+/// let colour = lambdas.to_colour(spectrum);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampledWavelengths {
+    /// The wavelengths, in nanometres. The first is the hero wavelength.
+    lambda: [f32; N_WAVELENGTHS],
+    /// Density each wavelength was sampled with, per nanometre.
+    pdf: [f32; N_WAVELENGTHS],
+}
+
+impl SampledWavelengths {
+    /// Maps a uniform sample in `[0, 1)` to a hero wavelength and the wavelengths spread from it,
+    /// distributed in proportion to how visible they are.
+    pub fn sample_visible(u: f32) -> Self {
+        let mut lambda = [0.0; N_WAVELENGTHS];
+        let mut pdf = [0.0; N_WAVELENGTHS];
+        for i in 0..N_WAVELENGTHS {
+            let u = (u + i as f32 / N_WAVELENGTHS as f32).fract();
+            lambda[i] = sample_visible_wavelength(u);
+            pdf[i] = visible_wavelength_pdf(lambda[i]);
+        }
+        Self { lambda, pdf }
+    }
+
+    /// The `i`th wavelength, in nanometres.
+    pub fn lambda(&self, i: usize) -> f32 {
+        self.lambda[i]
+    }
+
+    /// Density the `i`th wavelength was sampled with, or zero if it no longer carries light.
+    pub fn pdf(&self, i: usize) -> f32 {
+        self.pdf[i]
+    }
+
+    /// Stops carrying light at every wavelength but the hero wavelength, for when a path
+    /// scatters each wavelength in a different direction, such as through a prism, and can only
+    /// follow one of them. The light already found at the hero wavelength is weighted up so
+    /// the estimate of the colour stays correct on average.
+    pub fn terminate_secondary(&mut self) {
+        if self.secondary_terminated() {
+            return;
+        }
+        for pdf in self.pdf.iter_mut().skip(1) {
+            *pdf = 0.0;
+        }
+        self.pdf[0] /= N_WAVELENGTHS as f32;
+    }
+
+    /// Whether only the hero wavelength still carries light.
+    pub fn secondary_terminated(&self) -> bool {
+        self.pdf[1..].iter().all(|&pdf| pdf == 0.0)
+    }
+
+    /// Converts light sampled at these wavelengths to a linear sRGB colour, so that averaging
+    /// the colours of many paths gives the colour of the light over the whole spectrum.
+    pub fn to_colour(&self, spectrum: SampledSpectrum) -> Colour {
+        // Monte Carlo estimate of the integral of the spectrum against the colour matching
+        // functions.
+        let mut xyz = Vec3::ZERO;
+        for i in 0..N_WAVELENGTHS {
+            if self.pdf[i] > 0.0 {
+                xyz += spectrum[i] / self.pdf[i] * cie_xyz(self.lambda[i]);
+            }
+        }
+        let rgb = conversions().spectrum_to_rgb * (xyz / N_WAVELENGTHS as f32);
+        Colour::new(rgb.x, rgb.y, rgb.z)
+    }
+}
+
+/// Light, or a fraction of light, sampled at the wavelengths of a [`SampledWavelengths`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SampledSpectrum([f32; N_WAVELENGTHS]);
+
+impl SampledSpectrum {
+    /// Creates a spectrum from its value at each wavelength.
+    pub fn new(values: [f32; N_WAVELENGTHS]) -> Self {
+        Self(values)
+    }
+
+    /// Converts a linear sRGB colour to a smooth spectrum with that colour, sampled at
+    /// `lambdas`.
+    ///
+    /// The spectrum is a blend of three smooth red, green and blue spectra, which sum to one
+    /// everywhere, so white becomes a flat spectrum, and colours with every channel in `[0, 1]`,
+    /// such as albedos, become spectra between zero and one. The conversion is linear, so it
+    /// works the same for the colours of lights.
+    pub fn from_colour(colour: Colour, lambdas: &SampledWavelengths) -> Self {
+        let weights = conversions().rgb_to_basis * Vec3::new(colour.r(), colour.g(), colour.b());
+        Self(lambdas.lambda.map(|lambda| weights.dot(basis(lambda))))
+    }
+
+    /// The value at the wavelength `lambda`, in nanometres, of the spectrum
+    /// [`SampledSpectrum::from_colour`] converts `colour` to.
+    pub fn colour_at_wavelength(colour: Colour, lambda: f32) -> f32 {
+        let weights = conversions().rgb_to_basis * Vec3::new(colour.r(), colour.g(), colour.b());
+        weights.dot(basis(lambda))
+    }
+
+    /// The largest value at any wavelength.
+    pub fn max_component(&self) -> f32 {
+        self.0.iter().copied().fold(f32::MIN, f32::max)
+    }
+}
+
+impl Index<usize> for SampledSpectrum {
+    type Output = f32;
+
+    fn index(&self, i: usize) -> &f32 {
+        &self.0[i]
+    }
+}
+
+impl Mul for SampledSpectrum {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] * rhs.0[i]))
+    }
+}
+
+impl Mul<f32> for SampledSpectrum {
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self {
+        Self(self.0.map(|value| value * rhs))
+    }
+}
+
+impl Add for SampledSpectrum {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] + rhs.0[i]))
+    }
+}
+
+impl AddAssign for SampledSpectrum {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+/// The colour of a surface that reflects the fraction `reflectance(λ)` of white light of each
+/// wavelength `λ`, in nanometres, integrating over visible light in 5nm steps.
+///
+/// # Example
+/// ```
+/// use ray_tracing::spectrum::reflectance_colour;
+///
+/// // A surface reflecting only long wavelengths is red.
+/// let colour = reflectance_colour(|lambda| if lambda > 600.0 { 1.0 } else { 0.0 });
+/// assert!(colour.r() > 0.5 && colour.g() < 0.1 && colour.b() < 0.1);
+/// ```
+pub fn reflectance_colour(reflectance: impl Fn(f32) -> f32) -> Colour {
+    const STEP: f32 = 5.0;
+    let steps = ((LAMBDA_MAX - LAMBDA_MIN) / STEP) as usize;
+    let xyz: Vec3 = (0..steps)
+        .map(|step| {
+            let lambda = LAMBDA_MIN + (step as f32 + 0.5) * STEP;
+            reflectance(lambda) * STEP * cie_xyz(lambda)
+        })
+        .sum();
+    let rgb = conversions().spectrum_to_rgb * xyz;
+    Colour::new(rgb.x, rgb.y, rgb.z)
+}
+
+/// Density of [`sample_visible_wavelength`], per nanometre, roughly following the sensitivity
+/// of the eye.
+fn visible_wavelength_pdf(lambda: f32) -> f32 {
+    if !(LAMBDA_MIN..=LAMBDA_MAX).contains(&lambda) {
+        return 0.0;
+    }
+    0.003_939_804 / (0.0072 * (lambda - 538.0)).cosh().powi(2)
+}
+
+/// Maps a uniform sample in `[0, 1)` to a wavelength between [`LAMBDA_MIN`] and [`LAMBDA_MAX`],
+/// by inverting the distribution of [`visible_wavelength_pdf`].
+fn sample_visible_wavelength(u: f32) -> f32 {
+    538.0 - 138.888_89 * (0.856_910_6 - 1.827_502 * u).atanh()
+}
+
+/// The CIE 1931 colour matching functions at `lambda`, using the multi-lobe Gaussian fit of
+/// Wyman, Sloan and Shirley.
+fn cie_xyz(lambda: f32) -> Vec3 {
+    // A Gaussian with different widths either side of its peak.
+    let g = |mu: f32, sigma_below: f32, sigma_above: f32| {
+        let sigma = if lambda < mu {
+            sigma_below
+        } else {
+            sigma_above
+        };
+        (-0.5 * ((lambda - mu) / sigma).powi(2)).exp()
+    };
+    Vec3::new(
+        1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7) - 0.065 * g(501.1, 20.4, 26.2),
+        0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1),
+        1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8),
+    )
+}
+
+/// The smooth red, green and blue spectra colours are built from, at `lambda`. Red rises above
+/// 588nm and blue falls below 494nm, with green filling the gap, so the three sum to one. The
+/// edges are just sharp enough for the spectra to be more saturated than the sRGB primaries,
+/// so every sRGB colour is a blend of them with non-negative weights.
+fn basis(lambda: f32) -> Vec3 {
+    let sigmoid = |x: f32| 1.0 / (1.0 + (-x).exp());
+    let red = sigmoid((lambda - 588.0) / 3.0);
+    let blue = 1.0 - sigmoid((lambda - 494.0) / 3.0);
+    Vec3::new(red, 1.0 - red - blue, blue)
+}
+
+/// Linear transforms between spectra and colours, derived from the colour matching functions.
+struct Conversions {
+    /// From the integral of a spectrum against the colour matching functions to linear sRGB,
+    /// white balanced so a flat spectrum of one is white.
+    spectrum_to_rgb: Mat3,
+    /// From a linear sRGB colour to the weights of the [`basis`] spectra with that colour.
+    rgb_to_basis: Mat3,
+}
+
+impl Conversions {
+    fn new() -> Self {
+        let xyz_to_rgb = Mat3::from_cols(
+            Vec3::new(3.240_454, -0.969_266, 0.055_643),
+            Vec3::new(-1.537_139, 1.876_011, -0.204_026),
+            Vec3::new(-0.498_531, 0.041_556, 1.057_225),
+        );
+
+        // Integrate the flat spectrum and the basis spectra over 1nm steps.
+        let mut white = Vec3::ZERO;
+        let mut basis_xyz = Mat3::ZERO;
+        let steps = (LAMBDA_MAX - LAMBDA_MIN) as usize;
+        for step in 0..steps {
+            let lambda = LAMBDA_MIN + step as f32 + 0.5;
+            let xyz = cie_xyz(lambda);
+            let weights = basis(lambda);
+            white += xyz;
+            basis_xyz += Mat3::from_cols(weights.x * xyz, weights.y * xyz, weights.z * xyz);
+        }
+
+        let white_rgb = xyz_to_rgb * white;
+        let spectrum_to_rgb = Mat3::from_diagonal(white_rgb.recip()) * xyz_to_rgb;
+        Self {
+            spectrum_to_rgb,
+            rgb_to_basis: (spectrum_to_rgb * basis_xyz).inverse(),
+        }
+    }
+}
+
+/// The conversions, computed on first use.
+fn conversions() -> &'static Conversions {
+    static CONVERSIONS: OnceLock<Conversions> = OnceLock::new();
+    CONVERSIONS.get_or_init(Conversions::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The colour of `colour`'s spectrum, estimated from many stratified sets of wavelengths.
+    fn round_trip(colour: Colour) -> Colour {
+        let n = 1024;
+        let mut sum = Colour::default();
+        for k in 0..n {
+            let lambdas = SampledWavelengths::sample_visible((k as f32 + 0.5) / n as f32);
+            sum += lambdas.to_colour(SampledSpectrum::from_colour(colour, &lambdas));
+        }
+        sum * (1.0 / n as f32)
+    }
+
+    #[test]
+    fn wavelength_density_integrates_to_one() {
+        let integral: f32 = (0..4700)
+            .map(|k| visible_wavelength_pdf(LAMBDA_MIN + (k as f32 + 0.5) * 0.1) * 0.1)
+            .sum();
+        assert!((integral - 1.0).abs() < 1e-3, "{integral}");
+        assert!((sample_visible_wavelength(0.0) - LAMBDA_MIN).abs() < 0.5);
+        assert!((sample_visible_wavelength(0.999_999) - LAMBDA_MAX).abs() < 0.5);
+    }
+
+    #[test]
+    fn colours_survive_the_round_trip() {
+        for colour in [
+            Colour::new(1.0, 1.0, 1.0),
+            Colour::new(1.0, 0.0, 0.0),
+            Colour::new(0.0, 1.0, 0.0),
+            Colour::new(0.0, 0.0, 1.0),
+            Colour::new(0.8, 0.3, 0.1),
+            Colour::new(4.0, 2.0, 0.5),
+        ] {
+            let d = round_trip(colour) - colour;
+            let error = d.r().abs().max(d.g().abs()).max(d.b().abs());
+            assert!(error < 0.01 * colour.max_component(), "{colour}: {error}");
+        }
+    }
+
+    #[test]
+    fn terminating_secondary_wavelengths_keeps_colours() {
+        let colour = Colour::new(0.8, 0.3, 0.1);
+        let n = 1024;
+        let mut sum = Colour::default();
+        for k in 0..n {
+            let mut lambdas = SampledWavelengths::sample_visible((k as f32 + 0.5) / n as f32);
+            let spectrum = SampledSpectrum::from_colour(colour, &lambdas);
+            lambdas.terminate_secondary();
+            assert!(lambdas.secondary_terminated());
+            sum += lambdas.to_colour(spectrum);
+        }
+        let d = sum * (1.0 / n as f32) - colour;
+        assert!(d.r().abs().max(d.g().abs()).max(d.b().abs()) < 0.01);
+    }
+
+    #[test]
+    fn reflectance_colours_match_uplifted_colours() {
+        for colour in [Colour::new(1.0, 1.0, 1.0), Colour::new(0.8, 0.3, 0.1)] {
+            let d =
+                reflectance_colour(|lambda| SampledSpectrum::colour_at_wavelength(colour, lambda))
+                    - colour;
+            assert!(
+                d.r().abs().max(d.g().abs()).max(d.b().abs()) < 0.01,
+                "{colour}"
+            );
+        }
+    }
+
+    #[test]
+    fn albedos_become_reflectances() {
+        // A flat spectrum is white, and albedos never reflect more than all the light.
+        for k in 0..64 {
+            let lambdas = SampledWavelengths::sample_visible(k as f32 / 64.0);
+            let white = SampledSpectrum::from_colour(Colour::new(1.0, 1.0, 1.0), &lambdas);
+            for i in 0..N_WAVELENGTHS {
+                assert!((white[i] - 1.0).abs() < 1e-4);
+            }
+            for colour in [Colour::new(1.0, 0.0, 0.0), Colour::new(0.0, 1.0, 1.0)] {
+                let spectrum = SampledSpectrum::from_colour(colour, &lambdas);
+                for i in 0..N_WAVELENGTHS {
+                    assert!((-1e-4..=1.0001).contains(&spectrum[i]), "{}", spectrum[i]);
+                }
+            }
+        }
+    }
+}
@@ -2,9 +2,11 @@ use crate::{
     hittable::{HitRecord, Hittable},
     interval::Interval,
     material::Material,
-    Point3,
+    sampler::sample_unit_vector,
+    util::Onb,
+    Point3, Ray, Vec2, Vec3,
 };
-use std::rc::Rc;
+use std::{f32::consts::PI, rc::Rc};
 
 /// A sphere in 3D space, defined by its center, radius, and material.
 pub struct Sphere {
@@ -72,12 +74,51 @@ impl Hittable for Sphere {
         rec.p = r.at(rec.t);
         rec.normal = (rec.p - self.center) / self.radius;
         let outward_normal = (rec.p - self.center) / self.radius;
-        rec.set_face_normal(&r, &outward_normal);
+        rec.set_face_normal(r, &outward_normal);
 
-        return true;
+        true
     }
 
     fn mat(&self) -> Option<Rc<dyn Material>> {
         Some(self.mat.clone())
     }
+
+    /// The density of sampling `direction` uniformly from the cone of directions from `origin`
+    /// that hit the sphere.
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f32 {
+        let mut rec = HitRecord::default();
+        if !self.hit(
+            &Ray::new(origin, direction),
+            Interval::new(0.001, f32::INFINITY),
+            &mut rec,
+        ) {
+            return 0.0;
+        }
+
+        let distance_squared = (self.center - origin).length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            // Every direction hits the sphere from inside it.
+            return 1.0 / (4.0 * PI);
+        }
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        let solid_angle = 2.0 * PI * (1.0 - cos_theta_max);
+        1.0 / solid_angle
+    }
+
+    /// Samples a direction uniformly from the cone of directions from `origin` that hit the
+    /// sphere.
+    fn random(&self, origin: Point3, u: Vec2) -> Vec3 {
+        let direction = self.center - origin;
+        let distance_squared = direction.length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            return sample_unit_vector(u);
+        }
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        let z = 1.0 + u.y * (cos_theta_max - 1.0);
+        let phi = 2.0 * PI * u.x;
+        let r = (1.0 - z * z).max(0.0).sqrt();
+        Onb::new(direction).transform(Vec3::new(r * phi.cos(), r * phi.sin(), z))
+    }
 }
@@ -0,0 +1,158 @@
+use crate::{hittable::HitRecord, Colour};
+use std::rc::Rc;
+
+/// A surface property that can vary over a surface, such as its colour or roughness.
+///
+/// Scalar properties read the red channel, so a grey texture gives the same value whichever
+/// channel is read. A plain [`Colour`] or `f32` is a texture with the same value everywhere.
+pub trait Texture {
+    /// The value of the texture at the hit point `rec`.
+    fn value(&self, rec: &HitRecord) -> Colour;
+}
+
+impl Texture for Colour {
+    fn value(&self, _rec: &HitRecord) -> Colour {
+        *self
+    }
+}
+
+impl Texture for f32 {
+    fn value(&self, _rec: &HitRecord) -> Colour {
+        Colour::new(*self, *self, *self)
+    }
+}
+
+/// Shared textures, such as one packed texture read by several [`Channel`]s.
+impl<T: Texture + ?Sized> Texture for Rc<T> {
+    fn value(&self, rec: &HitRecord) -> Colour {
+        self.as_ref().value(rec)
+    }
+}
+
+/// A 3D checkerboard alternating between two textures in cubes of a fixed size.
+///
+/// # Example
+/// ```
+/// use ray_tracing::{hittable::HitRecord, texture::{Checker, Texture}, Colour, Point3};
+///
+/// let checker = Checker::new(1.0, Colour::new(1.0, 1.0, 1.0), Colour::new(0.0, 0.0, 0.0));
+/// let mut rec = HitRecord::default();
+/// rec.p = Point3::new(0.5, 0.5, 0.5);
+/// assert_eq!(checker.value(&rec), Colour::new(1.0, 1.0, 1.0));
+/// rec.p = Point3::new(1.5, 0.5, 0.5);
+/// assert_eq!(checker.value(&rec), Colour::new(0.0, 0.0, 0.0));
+/// ```
+pub struct Checker {
+    /// Edge length of each cube.
+    scale: f32,
+    /// Texture of the cube at the origin and every other cube from it.
+    even: Rc<dyn Texture>,
+    /// Texture of the remaining cubes.
+    odd: Rc<dyn Texture>,
+}
+
+impl Checker {
+    /// Creates a new checkerboard with cubes of edge length `scale`.
+    pub fn new(scale: f32, even: impl Texture + 'static, odd: impl Texture + 'static) -> Self {
+        Self {
+            scale,
+            even: Rc::new(even),
+            odd: Rc::new(odd),
+        }
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, rec: &HitRecord) -> Colour {
+        let cell = (rec.p / self.scale).floor();
+        if (cell.x + cell.y + cell.z) as i64 % 2 == 0 {
+            self.even.value(rec)
+        } else {
+            self.odd.value(rec)
+        }
+    }
+}
+
+/// One channel of another texture, in every channel. Useful for textures packing several scalar
+/// properties into one image, such as glTF's metallic-roughness textures, which hold roughness in
+/// the green channel and metallic in the blue channel.
+///
+/// # Example
+/// ```
+/// use ray_tracing::{hittable::HitRecord, texture::{Channel, Texture}, Colour};
+///
+/// let packed = Colour::new(1.0, 0.25, 0.75);
+/// let roughness = Channel::green(packed);
+/// assert_eq!(roughness.value(&HitRecord::default()), Colour::new(0.25, 0.25, 0.25));
+/// ```
+pub struct Channel {
+    /// The texture to read.
+    texture: Rc<dyn Texture>,
+    /// Index of the channel to read: 0 for red, 1 for green and 2 for blue.
+    index: usize,
+}
+
+impl Channel {
+    /// The red channel of `texture`.
+    pub fn red(texture: impl Texture + 'static) -> Self {
+        Self::new(texture, 0)
+    }
+
+    /// The green channel of `texture`.
+    pub fn green(texture: impl Texture + 'static) -> Self {
+        Self::new(texture, 1)
+    }
+
+    /// The blue channel of `texture`.
+    pub fn blue(texture: impl Texture + 'static) -> Self {
+        Self::new(texture, 2)
+    }
+
+    fn new(texture: impl Texture + 'static, index: usize) -> Self {
+        Self {
+            texture: Rc::new(texture),
+            index,
+        }
+    }
+}
+
+impl Texture for Channel {
+    fn value(&self, rec: &HitRecord) -> Colour {
+        let colour = self.texture.value(rec);
+        let value = [colour.r(), colour.g(), colour.b()][self.index];
+        Colour::new(value, value, value)
+    }
+}
+
+/// Another texture multiplied by a constant factor, such as the factors glTF multiplies its
+/// textures by.
+///
+/// # Example
+/// ```
+/// use ray_tracing::{hittable::HitRecord, texture::{Scale, Texture}, Colour};
+///
+/// let tinted = Scale::new(Colour::new(1.0, 0.5, 0.5), Colour::new(0.5, 1.0, 1.0));
+/// assert_eq!(tinted.value(&HitRecord::default()), Colour::new(0.5, 0.5, 0.5));
+/// ```
+pub struct Scale {
+    /// The texture being scaled.
+    texture: Rc<dyn Texture>,
+    /// Factor multiplying each channel.
+    factor: Colour,
+}
+
+impl Scale {
+    /// Creates `texture` multiplied by `factor`.
+    pub fn new(texture: impl Texture + 'static, factor: Colour) -> Self {
+        Self {
+            texture: Rc::new(texture),
+            factor,
+        }
+    }
+}
+
+impl Texture for Scale {
+    fn value(&self, rec: &HitRecord) -> Colour {
+        self.texture.value(rec) * self.factor
+    }
+}
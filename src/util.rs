@@ -60,7 +60,7 @@ pub fn reflect(v: &Vec3, n: &Vec3) -> Vec3 {
 /// );
 /// ```
 pub fn refract(uv: &Vec3, n: &Vec3, etai_over_etat: f32) -> Vec3 {
-    let cos_theta = f32::min(-1.0 * uv.dot(*n), 1.0);
+    let cos_theta = f32::min(-uv.dot(*n), 1.0);
     let r_out_perp = etai_over_etat * (uv + cos_theta * n);
     let r_out_parallel = -((1.0 - r_out_perp.length_squared()).abs()).sqrt() * n;
     r_out_perp + r_out_parallel
@@ -86,3 +86,69 @@ pub fn is_vec3_near_zero(v: Vec3) -> bool {
     let s = 1e-8_f32;
     (v.x.abs() < s) && (v.y.abs() < s) && v.z.abs() < s
 }
+
+/// An orthonormal basis, used to build directions relative to a surface normal.
+#[derive(Debug, Clone, Copy)]
+pub struct Onb {
+    /// The basis axes, with the chosen direction as the last one.
+    axis: [Vec3; 3],
+}
+
+impl Onb {
+    /// Creates a new orthonormal basis whose `w` axis points along `n`.
+    ///
+    /// # Parameters
+    /// - `n`: The direction of the `w` axis. Need not be normalised.
+    ///
+    /// # Returns
+    /// A new `Onb` with arbitrary, but consistent, `u` and `v` axes perpendicular to `n`.
+    pub fn new(n: Vec3) -> Self {
+        let w = n.normalize();
+        let (u, v) = w.any_orthonormal_pair();
+        Self { axis: [u, v, w] }
+    }
+
+    /// The first axis perpendicular to `w`.
+    pub fn u(&self) -> Vec3 {
+        self.axis[0]
+    }
+
+    /// The second axis perpendicular to `w`.
+    pub fn v(&self) -> Vec3 {
+        self.axis[1]
+    }
+
+    /// The axis the basis was built around.
+    pub fn w(&self) -> Vec3 {
+        self.axis[2]
+    }
+
+    /// Transforms a vector from coordinates in this basis to world coordinates.
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{util::Onb, Vec3};
+    /// let uvw = Onb::new(Vec3::new(0.0, 2.0, 0.0));
+    /// assert!((uvw.transform(Vec3::Z) - Vec3::Y).length() < 1e-6);
+    /// ```
+    pub fn transform(&self, v: Vec3) -> Vec3 {
+        v.x * self.axis[0] + v.y * self.axis[1] + v.z * self.axis[2]
+    }
+
+    /// Transforms a vector from world coordinates to coordinates in this basis, undoing
+    /// [`Onb::transform`].
+    ///
+    /// # Example
+    /// ```
+    /// use ray_tracing::{util::Onb, Vec3};
+    /// let uvw = Onb::new(Vec3::new(0.0, 2.0, 0.0));
+    /// assert!((uvw.to_local(Vec3::Y) - Vec3::Z).length() < 1e-6);
+    /// ```
+    pub fn to_local(&self, v: Vec3) -> Vec3 {
+        Vec3::new(
+            v.dot(self.axis[0]),
+            v.dot(self.axis[1]),
+            v.dot(self.axis[2]),
+        )
+    }
+}